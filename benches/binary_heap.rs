@@ -0,0 +1,46 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+
+use algo::bst::binary_heap::MinHeap;
+
+mod common;
+
+use common::{random, SIZES};
+
+fn binary_heap_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("binary_heap");
+    group.sample_size(10);
+
+    for &size in SIZES {
+        let array = random(size, 1);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("build", size), &array, |b, array| {
+            b.iter(|| MinHeap::build(array))
+        });
+
+        group.bench_with_input(BenchmarkId::new("insert_key", size), &array, |b, array| {
+            b.iter(|| {
+                let mut heap = MinHeap::with_capacity(array.len());
+
+                for &value in array {
+                    heap.insert_key(value);
+                }
+
+                heap
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("extract_min", size), &array, |b, array| {
+            b.iter_batched_ref(
+                || MinHeap::build(array),
+                |heap| (0..size).map(|_| heap.extract_min()).max(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, binary_heap_bench);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::bubble::bubble_sort;
+
+mod common;
+
+use common::{bench_sort, QUADRATIC_SIZES};
+
+fn bubble_sort_bench(c: &mut Criterion) {
+    bench_sort(
+        c,
+        "bubble_sort",
+        QUADRATIC_SIZES,
+        |value| value,
+        bubble_sort,
+    );
+}
+
+criterion_group!(benches, bubble_sort_bench);
+criterion_main!(benches);
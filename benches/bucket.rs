@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::bucket::bucket_sort;
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn bucket_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "bucket_sort", SIZES, |value| value as f64, bucket_sort);
+}
+
+criterion_group!(benches, bucket_sort_bench);
+criterion_main!(benches);
@@ -0,0 +1,119 @@
+// Inputs shared by the benchmarks. Every benchmark is its own target,
+// gated by the cargo feature of the code it measures, and pulls in
+// only the parts of this module it needs.
+#![allow(dead_code)]
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput};
+
+/// Sizes for the O(n log n) sorts.
+pub static SIZES: &[usize] = &[100, 10_000, 1_000_000];
+
+/// Sizes for the O(n^2) sorts.
+pub static QUADRATIC_SIZES: &[usize] = &[100, 1_000, 10_000];
+
+/// Length of the texts searched by the string benchmarks.
+pub static TEXT_SIZE: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Distribution {
+    Random,
+    Sorted,
+    Reversed,
+    /// Only 16 distinct values.
+    FewUnique,
+    /// Sorted runs of 64 values.
+    Sawtooth,
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 5] = [
+        Distribution::Random,
+        Distribution::Sorted,
+        Distribution::Reversed,
+        Distribution::FewUnique,
+        Distribution::Sawtooth,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Distribution::Random => "random",
+            Distribution::Sorted => "sorted",
+            Distribution::Reversed => "reversed",
+            Distribution::FewUnique => "few_unique",
+            Distribution::Sawtooth => "sawtooth",
+        }
+    }
+
+    pub fn generate(self, size: usize) -> Vec<u32> {
+        match self {
+            Distribution::Random => random(size, 1),
+            Distribution::Sorted => (0..size as u32).collect(),
+            Distribution::Reversed => (0..size as u32).rev().collect(),
+            Distribution::FewUnique => random(size, 1)
+                .into_iter()
+                .map(|value| value % 16)
+                .collect(),
+            Distribution::Sawtooth => (0..size as u32).map(|value| value % 64).collect(),
+        }
+    }
+}
+
+/// Xorshift values, the same for the same seed on every run.
+pub fn random(size: usize, seed: u64) -> Vec<u32> {
+    let mut state = seed.max(1);
+
+    (0..size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 32) as u32
+        })
+        .collect()
+}
+
+/// Random text over the first `alphabet` lowercase letters.
+pub fn text(size: usize, alphabet: u32) -> String {
+    random(size, 2)
+        .into_iter()
+        .map(|value| char::from(b'a' + (value % alphabet) as u8))
+        .collect()
+}
+
+/// Benchmarks `sort` on every distribution and size, with the values
+/// converted by `convert`. Every iteration sorts a fresh copy.
+pub fn bench_sort<T, S>(
+    c: &mut Criterion,
+    name: &str,
+    sizes: &[usize],
+    convert: fn(u32) -> T,
+    mut sort: S,
+) where
+    T: Clone,
+    S: FnMut(&mut [T]),
+{
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+
+    for &size in sizes {
+        group.throughput(Throughput::Elements(size as u64));
+
+        for distribution in Distribution::ALL {
+            let array: Vec<T> = distribution
+                .generate(size)
+                .into_iter()
+                .map(convert)
+                .collect();
+
+            group.bench_with_input(
+                BenchmarkId::new(distribution.name(), size),
+                &array,
+                |b, array| {
+                    b.iter_batched_ref(|| array.clone(), |array| sort(array), BatchSize::LargeInput)
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
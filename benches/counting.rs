@@ -0,0 +1,20 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::counting::counting_sort;
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn counting_sort_bench(c: &mut Criterion) {
+    bench_sort(
+        c,
+        "counting_sort",
+        SIZES,
+        |value| value as u16,
+        |array| counting_sort(array, u16::MAX as usize),
+    );
+}
+
+criterion_group!(benches, counting_sort_bench);
+criterion_main!(benches);
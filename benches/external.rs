@@ -0,0 +1,36 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use algo::sort::external::{BytesCodec, ExternalSorter};
+
+mod common;
+
+use common::{random, SIZES};
+
+fn external_sort_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("external_sort");
+    group.sample_size(10);
+
+    for &size in SIZES {
+        let input: Vec<u8> = random(size, 1)
+            .into_iter()
+            .flat_map(u32::to_be_bytes)
+            .collect();
+
+        // A budget of a tenth of the input makes about ten runs to merge
+        let sorter = ExternalSorter::new(BytesCodec::new(4)).with_memory_budget(input.len() / 10);
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &input, |b, input| {
+            b.iter(|| {
+                let mut output = Vec::with_capacity(input.len());
+                sorter.sort(&input[..], &mut output).unwrap();
+                output
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, external_sort_bench);
+criterion_main!(benches);
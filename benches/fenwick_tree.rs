@@ -0,0 +1,43 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use algo::bst::fenwick_tree::FenwickTree;
+
+mod common;
+
+use common::{random, SIZES};
+
+static OPERATIONS: usize = 10_000;
+
+fn fenwick_tree_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fenwick_tree");
+
+    for &size in SIZES {
+        let array: Vec<i64> = random(size, 1).into_iter().map(i64::from).collect();
+        let indices: Vec<usize> = random(2 * OPERATIONS, 2)
+            .into_iter()
+            .map(|value| value as usize % size)
+            .collect();
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("build", size), &array, |b, array| {
+            b.iter(|| FenwickTree::build(array))
+        });
+
+        let tree = FenwickTree::build(&array);
+        group.throughput(Throughput::Elements(OPERATIONS as u64));
+
+        group.bench_with_input(BenchmarkId::new("sum", size), &indices, |b, indices| {
+            b.iter(|| {
+                indices
+                    .chunks(2)
+                    .map(|pair| tree.sum(pair[0].min(pair[1]), pair[0].max(pair[1])))
+                    .sum::<i64>()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, fenwick_tree_bench);
+criterion_main!(benches);
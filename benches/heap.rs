@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::heap::heap_sort;
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn heap_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "heap_sort", SIZES, |value| value, heap_sort);
+}
+
+criterion_group!(benches, heap_sort_bench);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::intro::intro_sort;
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn intro_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "intro_sort", SIZES, |value| value, intro_sort);
+}
+
+criterion_group!(benches, intro_sort_bench);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use algo::string::kmp::kmp;
+
+mod common;
+
+use common::{text, TEXT_SIZE};
+
+fn kmp_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("kmp");
+    group.throughput(Throughput::Bytes(TEXT_SIZE as u64));
+
+    for alphabet in [2, 26] {
+        let text = text(TEXT_SIZE, alphabet).into_bytes();
+
+        for pattern_size in [4, 256] {
+            let pattern = &text[TEXT_SIZE / 2..TEXT_SIZE / 2 + pattern_size];
+
+            group.bench_function(
+                format!("alphabet_{}/pattern_{}", alphabet, pattern_size),
+                |b| b.iter(|| kmp(pattern, &text)),
+            );
+        }
+    }
+
+    // Matches at every position
+    let text = text(TEXT_SIZE, 1).into_bytes();
+    group.bench_function("alphabet_1/pattern_256", |b| {
+        b.iter(|| kmp(&text[..256], &text))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, kmp_bench);
+criterion_main!(benches);
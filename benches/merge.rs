@@ -0,0 +1,18 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::merge::{merge_sort, tim_sort};
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn merge_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "merge_sort", SIZES, |value| value, merge_sort);
+}
+
+fn tim_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "tim_sort", SIZES, |value| value, tim_sort);
+}
+
+criterion_group!(benches, merge_sort_bench, tim_sort_bench);
+criterion_main!(benches);
@@ -0,0 +1,52 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use algo::sort::network::{binary_insertion_sort, insertion_sort, network_sort};
+
+mod common;
+
+use common::{bench_sort, random, QUADRATIC_SIZES};
+
+fn insertion_sort_bench(c: &mut Criterion) {
+    bench_sort(
+        c,
+        "insertion_sort",
+        QUADRATIC_SIZES,
+        |value| value,
+        insertion_sort,
+    );
+}
+
+fn binary_insertion_sort_bench(c: &mut Criterion) {
+    bench_sort(
+        c,
+        "binary_insertion_sort",
+        QUADRATIC_SIZES,
+        |value| value,
+        binary_insertion_sort,
+    );
+}
+
+fn network_sort_bench(c: &mut Criterion) {
+    let values = random(32, 1);
+    let mut group = c.benchmark_group("network_sort");
+
+    group.bench_function("8", |b| {
+        let array: [u32; 8] = std::array::from_fn(|i| values[i]);
+        b.iter(|| network_sort(&mut black_box(array)))
+    });
+
+    group.bench_function("32", |b| {
+        let array: [u32; 32] = std::array::from_fn(|i| values[i]);
+        b.iter(|| network_sort(&mut black_box(array)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    insertion_sort_bench,
+    binary_insertion_sort_bench,
+    network_sort_bench
+);
+criterion_main!(benches);
@@ -0,0 +1,18 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::parallel::{par_merge_sort, par_quick_sort};
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn par_merge_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "par_merge_sort", SIZES, |value| value, par_merge_sort);
+}
+
+fn par_quick_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "par_quick_sort", SIZES, |value| value, par_quick_sort);
+}
+
+criterion_group!(benches, par_merge_sort_bench, par_quick_sort_bench);
+criterion_main!(benches);
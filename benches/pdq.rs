@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::pdq::pdq_sort;
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn pdq_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "pdq_sort", SIZES, |value| value, pdq_sort);
+}
+
+criterion_group!(benches, pdq_sort_bench);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use algo::string::prefix_function::prefix_function;
+
+mod common;
+
+use common::{text, TEXT_SIZE};
+
+fn prefix_function_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prefix_function");
+    group.throughput(Throughput::Bytes(TEXT_SIZE as u64));
+
+    // Few letters make long borders, a single one is the worst case
+    for alphabet in [1, 2, 26] {
+        let text = text(TEXT_SIZE, alphabet);
+        group.bench_function(format!("alphabet_{}", alphabet), |b| {
+            b.iter(|| prefix_function(text.as_bytes()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, prefix_function_bench);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::quick::quick_sort;
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn quick_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "quick_sort", SIZES, |value| value, quick_sort);
+}
+
+criterion_group!(benches, quick_sort_bench);
+criterion_main!(benches);
@@ -0,0 +1,19 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::radix::{msd_radix_sort, radix_sort};
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn radix_sort_bench(c: &mut Criterion) {
+    bench_sort(c, "radix_sort", SIZES, |value| value, radix_sort);
+}
+
+fn msd_radix_sort_bench(c: &mut Criterion) {
+    // Big endian bytes order the same as the numbers
+    bench_sort(c, "msd_radix_sort", SIZES, u32::to_be_bytes, msd_radix_sort);
+}
+
+criterion_group!(benches, radix_sort_bench, msd_radix_sort_bench);
+criterion_main!(benches);
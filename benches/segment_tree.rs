@@ -0,0 +1,56 @@
+use std::num::Wrapping;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use algo::bst::segment_tree::SegmentTree;
+
+mod common;
+
+use common::{random, SIZES};
+
+static OPERATIONS: usize = 10_000;
+
+fn segment_tree_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("segment_tree");
+
+    for &size in SIZES {
+        let array: Vec<Wrapping<u64>> = random(size, 1)
+            .into_iter()
+            .map(|value| Wrapping(value as u64))
+            .collect();
+        let indices: Vec<usize> = random(2 * OPERATIONS, 2)
+            .into_iter()
+            .map(|value| value as usize % size)
+            .collect();
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("build", size), &array, |b, array| {
+            b.iter(|| SegmentTree::build(array))
+        });
+
+        let mut tree = SegmentTree::build(&array);
+        group.throughput(Throughput::Elements(OPERATIONS as u64));
+
+        group.bench_with_input(BenchmarkId::new("product", size), &indices, |b, indices| {
+            b.iter(|| {
+                indices
+                    .chunks(2)
+                    .map(|pair| tree.product(pair[0].min(pair[1]), pair[0].max(pair[1])))
+                    .sum::<Wrapping<u64>>()
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("update", size), &indices, |b, indices| {
+            b.iter(|| {
+                for pair in indices.chunks(2) {
+                    tree.update(pair[0], Wrapping(pair[1] as u64));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, segment_tree_bench);
+criterion_main!(benches);
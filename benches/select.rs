@@ -0,0 +1,62 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use algo::sort::select::{partial_sort, select_nth, select_nth_deterministic, top_k};
+
+mod common;
+
+use common::{bench_sort, SIZES};
+
+fn select_nth_bench(c: &mut Criterion) {
+    bench_sort(
+        c,
+        "select_nth",
+        SIZES,
+        |value| value,
+        |array| {
+            select_nth(array, array.len() / 2);
+        },
+    );
+}
+
+fn select_nth_deterministic_bench(c: &mut Criterion) {
+    bench_sort(
+        c,
+        "select_nth_deterministic",
+        SIZES,
+        |value| value,
+        |array| {
+            select_nth_deterministic(array, array.len() / 2);
+        },
+    );
+}
+
+fn partial_sort_bench(c: &mut Criterion) {
+    bench_sort(
+        c,
+        "partial_sort",
+        SIZES,
+        |value| value,
+        |array| partial_sort(array, 100),
+    );
+}
+
+fn top_k_bench(c: &mut Criterion) {
+    bench_sort(
+        c,
+        "top_k",
+        SIZES,
+        |value| value,
+        |array| {
+            top_k(array, 100);
+        },
+    );
+}
+
+criterion_group!(
+    benches,
+    select_nth_bench,
+    select_nth_deterministic_bench,
+    partial_sort_bench,
+    top_k_bench
+);
+criterion_main!(benches);
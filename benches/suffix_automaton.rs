@@ -0,0 +1,45 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use algo::string::suffix_automaton::SuffixAutomaton;
+
+mod common;
+
+use common::{random, text, TEXT_SIZE};
+
+fn suffix_automaton_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("suffix_automaton");
+    group.sample_size(10);
+
+    for alphabet in [2, 26] {
+        let text = text(TEXT_SIZE, alphabet);
+
+        group.throughput(Throughput::Bytes(TEXT_SIZE as u64));
+        group.bench_function(format!("build/alphabet_{}", alphabet), |b| {
+            b.iter(|| SuffixAutomaton::build(&text))
+        });
+
+        let automaton = SuffixAutomaton::build(&text);
+        let queries: Vec<&str> = random(1000, 3)
+            .into_iter()
+            .map(|start| {
+                let start = start as usize % (TEXT_SIZE - 64);
+                &text[start..start + 64]
+            })
+            .collect();
+
+        group.throughput(Throughput::Elements(queries.len() as u64));
+        group.bench_function(format!("contains/alphabet_{}", alphabet), |b| {
+            b.iter(|| {
+                queries
+                    .iter()
+                    .filter(|query| automaton.contains(query))
+                    .count()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, suffix_automaton_bench);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use algo::string::trie::Trie;
+
+mod common;
+
+use common::text;
+
+static WORDS: usize = 10_000;
+static WORD_SIZE: usize = 16;
+
+fn words(seed: u32) -> Vec<String> {
+    let text = text(WORDS * WORD_SIZE + seed as usize, 26);
+
+    (0..WORDS)
+        .map(|i| text[seed as usize + i * WORD_SIZE..][..WORD_SIZE].to_string())
+        .collect()
+}
+
+fn trie_bench(c: &mut Criterion) {
+    let words = words(0);
+    let mut group = c.benchmark_group("trie");
+    group.throughput(Throughput::Elements(WORDS as u64));
+
+    group.bench_function("add_string", |b| {
+        b.iter(|| {
+            let mut trie = Trie::new();
+
+            for word in &words {
+                trie.add_string(word);
+            }
+
+            trie
+        })
+    });
+
+    let mut trie = Trie::new();
+
+    for word in &words {
+        trie.add_string(word);
+    }
+
+    group.bench_function("contains_present", |b| {
+        b.iter(|| words.iter().filter(|word| trie.contains(word)).count())
+    });
+
+    let absent = self::words(1);
+    group.bench_function("contains_absent", |b| {
+        b.iter(|| absent.iter().filter(|word| trie.contains(word)).count())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, trie_bench);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use algo::string::z_function::z_function;
+
+mod common;
+
+use common::{text, TEXT_SIZE};
+
+fn z_function_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("z_function");
+    group.throughput(Throughput::Bytes(TEXT_SIZE as u64));
+
+    // Few letters make long matches, a single one is the worst case
+    for alphabet in [1, 2, 26] {
+        let text = text(TEXT_SIZE, alphabet);
+        group.bench_function(format!("alphabet_{}", alphabet), |b| {
+            b.iter(|| z_function(text.as_bytes()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, z_function_bench);
+criterion_main!(benches);
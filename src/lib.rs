@@ -6,7 +6,13 @@
     feature = "prefix_function",
     feature = "kmp",
     feature = "trie",
+    feature = "aho_corasick",
     feature = "suffix_automaton",
+    feature = "suffix_array",
+    feature = "suffix_tree",
+    feature = "generalized_suffix_automaton",
+    feature = "hash",
+    feature = "palindrome",
 ))]
 pub mod string;
 
@@ -25,5 +31,13 @@ pub mod bst;
     feature = "heap",
     feature = "radix",
     feature = "bubble",
+    feature = "intro",
+    feature = "pdq",
+    feature = "parallel",
+    feature = "external",
+    feature = "select",
+    feature = "counting",
+    feature = "bucket",
+    feature = "network",
 ))]
 pub mod sort;
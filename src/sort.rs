@@ -1,6 +1,15 @@
+pub mod sorter;
+
+pub mod stats;
+
+pub mod trace;
+
 #[cfg(feature = "bubble")]
 pub mod bubble;
 
+#[cfg(feature = "network")]
+pub mod network;
+
 #[cfg(feature = "quick")]
 pub mod quick;
 
@@ -9,3 +18,30 @@ pub mod merge;
 
 #[cfg(feature = "heap")]
 pub mod heap;
+
+#[cfg(feature = "radix")]
+pub mod radix;
+
+#[cfg(feature = "intro")]
+pub mod intro;
+
+#[cfg(feature = "pdq")]
+pub mod pdq;
+
+#[cfg(feature = "parallel")]
+pub mod parallel;
+
+#[cfg(feature = "external")]
+pub mod external;
+
+#[cfg(feature = "select")]
+pub mod select;
+
+#[cfg(feature = "counting")]
+pub mod counting;
+
+#[cfg(feature = "bucket")]
+pub mod bucket;
+
+#[cfg(test)]
+mod tests;
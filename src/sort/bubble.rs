@@ -1,22 +1,77 @@
-use std::cmp::Ord;
+use std::cmp::{Ord, Ordering};
+
+use crate::sort::sorter::Sorter;
+use crate::sort::stats;
+use crate::sort::trace;
 
 /// O(n^2) time (worst)\
 /// O(n) time (best)\
 /// O(n^2) time (average)\
-/// O(1) spacce\
+/// O(1) space\
 /// Stable
 pub fn bubble_sort<T>(array: &mut [T])
 where
     T: Ord,
+{
+    bubble_sort_by(array, T::cmp);
+}
+
+/// O(n^2) time (worst)\
+/// O(n) time (best)\
+/// O(n^2) time (average)\
+/// O(1) space\
+/// Stable
+pub fn bubble_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let size = array.len();
 
     for i in 0..size {
-        for j in (i + 1)..size {
-            if array[i] > array[j] {
-                array.swap(i, j)
+        let mut swapped = false;
+
+        for j in 1..(size - i) {
+            if compare(&array[j - 1], &array[j]) == Ordering::Greater {
+                array.swap(j - 1, j);
+                stats::swap();
+                trace::swap(array, j - 1, j);
+                swapped = true;
             }
         }
+
+        if !swapped {
+            break;
+        }
+    }
+}
+
+/// O(n^2) time (worst)\
+/// O(n) time (best)\
+/// O(n^2) time (average)\
+/// O(1) space\
+/// Stable
+pub fn bubble_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    bubble_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// `bubble_sort` as a `Sorter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BubbleSort;
+
+impl Sorter for BubbleSort {
+    const NAME: &'static str = "bubble_sort";
+    const STABLE: bool = true;
+    const IN_PLACE: bool = true;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        bubble_sort_by(array, compare);
     }
 }
 
@@ -39,4 +94,23 @@ mod tests {
 
         assert_eq!(array, ["a", "aa", "bab", "bac", "bac", "bbc", "ca"]);
     }
+
+    #[test]
+    fn bubble_sort_by_floats_test_1() {
+        let mut array = [2.5f64, -1.0, 0.0, 13.25, -7.5];
+        bubble_sort_by(&mut array, |a, b| b.total_cmp(a));
+
+        assert_eq!(array, [13.25, 2.5, 0.0, -1.0, -7.5]);
+    }
+
+    #[test]
+    fn bubble_sort_by_key_stable_test_1() {
+        let mut array = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e'), (2, 'f')];
+        bubble_sort_by_key(&mut array, |pair| pair.0);
+
+        assert_eq!(
+            array,
+            [(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c'), (2, 'f')]
+        );
+    }
 }
@@ -0,0 +1,110 @@
+extern crate num;
+
+use num::Float;
+
+use crate::sort::network::insertion_sort_helper;
+use crate::sort::stats;
+
+/// Expects the values to be spread uniformly over their range.
+/// Infinities go to the ends and NaNs after everything else.\
+/// O(n^2) time (worst)\
+/// O(n) time (best)\
+/// O(n) time (average)\
+/// O(n) space\
+/// Stable
+pub fn bucket_sort<T>(array: &mut [T])
+where
+    T: Float,
+{
+    let negative_infinities = array.iter().filter(|&&value| value == T::neg_infinity());
+    let positive_infinities = array.iter().filter(|&&value| value == T::infinity());
+    let nans = array.iter().filter(|value| value.is_nan());
+    let finite: Vec<T> = array
+        .iter()
+        .copied()
+        .filter(|value| value.is_finite())
+        .collect();
+
+    let mut sorted: Vec<T> = negative_infinities.copied().collect();
+
+    if let Some(&first) = finite.first() {
+        let (min, max) = finite.iter().fold((first, first), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        });
+
+        // Halved, so that the range can't overflow
+        let scale = max / T::from(2).unwrap() - min / T::from(2).unwrap();
+        let last = finite.len() - 1;
+        let mut buckets = vec![Vec::new(); finite.len()];
+
+        for &value in &finite {
+            let bucket = if scale > T::zero() {
+                let offset = value / T::from(2).unwrap() - min / T::from(2).unwrap();
+                (offset / scale * T::from(last).unwrap())
+                    .to_usize()
+                    .unwrap_or(last)
+            } else {
+                0
+            };
+
+            buckets[bucket.min(last)].push(value);
+        }
+
+        for mut bucket in buckets {
+            insertion_sort_helper(&mut bucket, &mut |a: &T, b: &T| a.partial_cmp(b).unwrap());
+            sorted.extend(bucket);
+        }
+    }
+
+    sorted.extend(positive_infinities);
+    sorted.extend(nans);
+
+    array.copy_from_slice(&sorted);
+    stats::moves(2 * sorted.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_sort_uniform_test_1() {
+        let mut array: Vec<f64> = (0..1000)
+            .map(|i| (i * 7919 % 1000) as f64 / 1000.0)
+            .collect();
+        let mut expected = array.clone();
+        expected.sort_by(f64::total_cmp);
+        bucket_sort(&mut array);
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn bucket_sort_special_values_test_1() {
+        let mut array = [
+            2.5f32,
+            f32::NAN,
+            f32::INFINITY,
+            -1.0,
+            f32::MAX,
+            f32::NEG_INFINITY,
+            -f32::MAX,
+            0.0,
+        ];
+        bucket_sort(&mut array);
+
+        assert_eq!(
+            array[..7],
+            [
+                f32::NEG_INFINITY,
+                -f32::MAX,
+                -1.0,
+                0.0,
+                2.5,
+                f32::MAX,
+                f32::INFINITY
+            ]
+        );
+        assert!(array[7].is_nan());
+    }
+}
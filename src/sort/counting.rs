@@ -0,0 +1,102 @@
+use crate::sort::stats;
+
+/// Keys must not exceed `max_key`.\
+/// O(n + k) time, k = max_key\
+/// O(n + k) space\
+/// Stable
+pub fn counting_sort<T>(array: &mut [T], max_key: usize)
+where
+    T: Copy + Into<usize>,
+{
+    counting_sort_by_key(array, max_key, |&item| item.into());
+}
+
+/// Keys must not exceed `max_key`.\
+/// O(n + k) time, k = max_key\
+/// O(n + k) space\
+/// Stable
+pub fn counting_sort_by_key<T, F>(array: &mut [T], max_key: usize, key: F)
+where
+    F: FnMut(&T) -> usize,
+{
+    let keys: Vec<usize> = array.iter().map(key).collect();
+    let mut offsets = vec![0; max_key + 2];
+
+    for &key in &keys {
+        assert!(
+            key <= max_key,
+            "key {} exceeds the maximum {}",
+            key,
+            max_key
+        );
+        offsets[key + 1] += 1;
+    }
+
+    for key in 1..offsets.len() {
+        offsets[key] += offsets[key - 1];
+    }
+
+    // Final position of every element, equal keys keep their order
+    let mut positions: Vec<usize> = keys
+        .iter()
+        .map(|&key| {
+            offsets[key] += 1;
+            offsets[key] - 1
+        })
+        .collect();
+
+    // Apply the permutation cycle by cycle, every swap puts one element in place
+    for i in 0..array.len() {
+        while positions[i] != i {
+            let target = positions[i];
+            array.swap(i, target);
+            stats::swap();
+            positions.swap(i, target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting_sort_numbers_test_1() {
+        let mut array = [5u8, 1, 2, 255, 35, 12, 1, 0];
+        counting_sort(&mut array, u8::MAX as usize);
+
+        assert_eq!(array, [0, 1, 1, 2, 5, 12, 35, 255]);
+    }
+
+    #[test]
+    fn counting_sort_by_key_stable_test_1() {
+        let mut array = [
+            ("ann", 4),
+            ("bob", 2),
+            ("cid", 5),
+            ("dan", 2),
+            ("eve", 4),
+            ("fay", 3),
+        ];
+        counting_sort_by_key(&mut array, 5, |student| student.1);
+
+        assert_eq!(
+            array,
+            [
+                ("bob", 2),
+                ("dan", 2),
+                ("fay", 3),
+                ("ann", 4),
+                ("eve", 4),
+                ("cid", 5)
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn counting_sort_out_of_bounds_test_1() {
+        let mut array = [1u8, 2, 3];
+        counting_sort(&mut array, 2);
+    }
+}
@@ -0,0 +1,443 @@
+use std::cmp::{Ord, Ordering};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicUsize};
+use std::{env, mem, process};
+
+use crate::sort::heap::heapify;
+use crate::sort::merge::merge_sort_by;
+
+static DEFAULT_MEMORY_BUDGET: usize = 64 << 20;
+static MAX_FAN_IN: usize = 64;
+
+static NEXT_SORTER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Fixed width binary encoding of records.
+pub trait RecordCodec {
+    type Record;
+
+    /// Size of an encoded record in bytes.
+    fn record_size(&self) -> usize;
+
+    /// `bytes` is exactly `record_size()` long.
+    fn encode(&self, record: &Self::Record, bytes: &mut [u8]);
+
+    /// `bytes` is exactly `record_size()` long.
+    fn decode(&self, bytes: &[u8]) -> Self::Record;
+}
+
+/// Raw records of `width` bytes, ordered lexicographically.
+#[derive(Debug, Clone, Copy)]
+pub struct BytesCodec {
+    width: usize,
+}
+
+impl BytesCodec {
+    pub fn new(width: usize) -> Self {
+        assert!(width > 0);
+
+        Self { width }
+    }
+}
+
+impl RecordCodec for BytesCodec {
+    type Record = Vec<u8>;
+
+    fn record_size(&self) -> usize {
+        self.width
+    }
+
+    fn encode(&self, record: &Vec<u8>, bytes: &mut [u8]) {
+        bytes.copy_from_slice(record);
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Vec<u8> {
+        bytes.to_vec()
+    }
+}
+
+/// Sorts streams of fixed width records that don't fit in memory.
+/// The input is split into runs that fit in the memory budget, every run
+/// is sorted with `merge_sort` and spilled to a temporary file, and the
+/// runs are k-way merged into the output.
+#[derive(Debug, Clone)]
+pub struct ExternalSorter<C>
+where
+    C: RecordCodec,
+{
+    codec: C,
+    memory_budget: usize,
+    temp_dir: PathBuf,
+}
+
+impl<C> ExternalSorter<C>
+where
+    C: RecordCodec,
+{
+    pub fn new(codec: C) -> Self {
+        assert!(codec.record_size() > 0);
+
+        Self {
+            codec,
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            temp_dir: env::temp_dir(),
+        }
+    }
+
+    /// Approximate number of bytes a single run may take in memory.
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    /// Directory for the run files, the system one by default.
+    pub fn with_temp_dir<P>(mut self, temp_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+
+    /// O(n log n) time\
+    /// O(memory budget) space\
+    /// Stable
+    pub fn sort<R, W>(&self, input: R, output: W) -> io::Result<()>
+    where
+        C::Record: Ord,
+        R: Read,
+        W: Write,
+    {
+        self.sort_by(input, output, C::Record::cmp)
+    }
+
+    /// O(n log n) time\
+    /// O(memory budget) space\
+    /// Stable
+    pub fn sort_by_key<R, W, K, F>(&self, input: R, output: W, mut key: F) -> io::Result<()>
+    where
+        R: Read,
+        W: Write,
+        F: FnMut(&C::Record) -> K,
+        K: Ord,
+    {
+        self.sort_by(input, output, |a, b| key(a).cmp(&key(b)))
+    }
+
+    /// O(n log n) time\
+    /// O(memory budget) space\
+    /// Stable
+    pub fn sort_by<R, W, F>(&self, input: R, output: W, mut compare: F) -> io::Result<()>
+    where
+        R: Read,
+        W: Write,
+        F: FnMut(&C::Record, &C::Record) -> Ordering,
+    {
+        let mut input = BufReader::new(input);
+        let mut output = BufWriter::new(output);
+
+        let mut run_files = RunFiles::new(&self.temp_dir);
+        let mut runs = Vec::new();
+        let mut run = Vec::new();
+
+        loop {
+            let exhausted = self.read_run(&mut input, &mut run)?;
+            merge_sort_by(&mut run, &mut compare);
+
+            // Everything fits in memory, no need for temporary files
+            if exhausted && runs.is_empty() {
+                self.write_run(&run, &mut output)?;
+                return output.flush();
+            }
+
+            if !run.is_empty() {
+                let path = run_files.create();
+                self.write_run(&run, BufWriter::new(File::create(&path)?))?;
+                runs.push(path);
+            }
+
+            if exhausted {
+                break;
+            }
+        }
+
+        // Keep the number of open files bounded
+        while runs.len() > MAX_FAN_IN {
+            let mut merged = Vec::new();
+
+            for group in runs.chunks(MAX_FAN_IN) {
+                let path = run_files.create();
+                self.merge_runs(group, BufWriter::new(File::create(&path)?), &mut compare)?;
+                merged.push(path);
+            }
+
+            for path in mem::replace(&mut runs, merged) {
+                fs::remove_file(path)?;
+            }
+        }
+
+        self.merge_runs(&runs, &mut output, &mut compare)?;
+        output.flush()
+    }
+
+    /// Number of records that fit in the memory budget, at least one.
+    /// Sorting a run takes a buffer of half its records on top.
+    fn run_length(&self) -> usize {
+        let record_size = mem::size_of::<C::Record>();
+        let record = self.codec.record_size() + record_size + record_size / 2;
+
+        (self.memory_budget / record).max(1)
+    }
+
+    /// Reads the next run into `run`. Returns `true` once the input is exhausted.
+    fn read_run<R>(&self, input: &mut R, run: &mut Vec<C::Record>) -> io::Result<bool>
+    where
+        R: Read,
+    {
+        let mut bytes = vec![0; self.codec.record_size()];
+        run.clear();
+
+        while run.len() < self.run_length() {
+            match self.read_record(input, &mut bytes)? {
+                Some(record) => run.push(record),
+                None => return Ok(true),
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// `Ok(None)` on a clean end of input, an error on a truncated record.
+    fn read_record<R>(&self, input: &mut R, bytes: &mut [u8]) -> io::Result<Option<C::Record>>
+    where
+        R: Read,
+    {
+        let mut filled = 0;
+
+        while filled < bytes.len() {
+            match input.read(&mut bytes[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "input ends in the middle of a record",
+                    ))
+                }
+                Ok(count) => filled += count,
+                Err(error) if error.kind() == ErrorKind::Interrupted => (),
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(Some(self.codec.decode(bytes)))
+    }
+
+    fn write_run<W>(&self, run: &[C::Record], mut output: W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let mut bytes = vec![0; self.codec.record_size()];
+
+        for record in run {
+            self.codec.encode(record, &mut bytes);
+            output.write_all(&bytes)?;
+        }
+
+        output.flush()
+    }
+
+    /// K-way merge with a heap of the current head of every run.
+    /// Ties go to the earlier run to keep the sort stable.
+    fn merge_runs<W, F>(&self, runs: &[PathBuf], mut output: W, compare: &mut F) -> io::Result<()>
+    where
+        W: Write,
+        F: FnMut(&C::Record, &C::Record) -> Ordering,
+    {
+        let mut bytes = vec![0; self.codec.record_size()];
+        let mut readers = Vec::with_capacity(runs.len());
+        let mut heap = Vec::with_capacity(runs.len());
+
+        for (index, path) in runs.iter().enumerate() {
+            let mut reader = BufReader::new(File::open(path)?);
+
+            if let Some(record) = self.read_record(&mut reader, &mut bytes)? {
+                heap.push((record, index));
+            }
+
+            readers.push(reader);
+        }
+
+        // `heapify` builds a max heap, so flip the order to get the least head on top
+        let mut reversed = |a: &(C::Record, usize), b: &(C::Record, usize)| {
+            compare(&b.0, &a.0).then(b.1.cmp(&a.1))
+        };
+
+        let size = heap.len();
+
+        for i in (0..size / 2).rev() {
+            heapify(&mut heap, size, i, &mut reversed);
+        }
+
+        while !heap.is_empty() {
+            let index = heap[0].1;
+            self.codec.encode(&heap[0].0, &mut bytes);
+            output.write_all(&bytes)?;
+
+            match self.read_record(&mut readers[index], &mut bytes)? {
+                Some(record) => heap[0].0 = record,
+                None => {
+                    heap.swap_remove(0);
+                }
+            }
+
+            let size = heap.len();
+            heapify(&mut heap, size, 0, &mut reversed);
+        }
+
+        output.flush()
+    }
+}
+
+/// Hands out unique run file paths and removes the files when dropped.
+struct RunFiles {
+    prefix: PathBuf,
+    created: usize,
+}
+
+impl RunFiles {
+    fn new(temp_dir: &Path) -> Self {
+        let id = NEXT_SORTER_ID.fetch_add(1, atomic::Ordering::Relaxed);
+        let name = format!("algo-external-{}-{}", process::id(), id);
+
+        Self {
+            prefix: temp_dir.join(name),
+            created: 0,
+        }
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        self.prefix.with_extension(format!("{}.run", index))
+    }
+
+    fn create(&mut self) -> PathBuf {
+        self.created += 1;
+        self.path(self.created - 1)
+    }
+}
+
+impl Drop for RunFiles {
+    fn drop(&mut self) {
+        for index in 0..self.created {
+            // Merged runs are already gone
+            let _ = fs::remove_file(self.path(index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    struct U32Codec;
+
+    impl RecordCodec for U32Codec {
+        type Record = u32;
+
+        fn record_size(&self) -> usize {
+            4
+        }
+
+        fn encode(&self, record: &u32, bytes: &mut [u8]) {
+            bytes.copy_from_slice(&record.to_be_bytes());
+        }
+
+        fn decode(&self, bytes: &[u8]) -> u32 {
+            u32::from_be_bytes(bytes.try_into().unwrap())
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("algo-external-test-{}-{}", name, process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    fn encode(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn external_sort_numbers_test_1() {
+        let dir = temp_dir("numbers");
+        let values: Vec<u32> = (0..10_000).map(|i| i * 7919 % 10_007).collect();
+
+        // Forces 200 runs and two merge passes
+        let sorter = ExternalSorter::new(U32Codec)
+            .with_memory_budget(50 * 10)
+            .with_temp_dir(&dir);
+
+        let mut output = Vec::new();
+        sorter
+            .sort(Cursor::new(encode(&values)), &mut output)
+            .unwrap();
+
+        let mut expected = values;
+        expected.sort();
+
+        assert_eq!(output, encode(&expected));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn external_sort_files_test_1() {
+        let dir = temp_dir("files");
+        let input_path = dir.join("input");
+        let output_path = dir.join("output");
+
+        let records: Vec<[u8; 3]> = (0..3000u32)
+            .map(|i| {
+                let value = i * 7919 % 3001;
+                [(value % 7) as u8, (value / 256) as u8, value as u8]
+            })
+            .collect();
+        fs::write(&input_path, records.concat()).unwrap();
+
+        let sorter = ExternalSorter::new(BytesCodec::new(3))
+            .with_memory_budget(1000)
+            .with_temp_dir(&dir);
+
+        // Stable by the first byte only
+        sorter
+            .sort_by_key(
+                File::open(&input_path).unwrap(),
+                File::create(&output_path).unwrap(),
+                |record| record[0],
+            )
+            .unwrap();
+
+        let mut expected = records;
+        expected.sort_by_key(|record| record[0]);
+
+        assert_eq!(fs::read(&output_path).unwrap(), expected.concat());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn external_sort_truncated_test_1() {
+        let sorter = ExternalSorter::new(U32Codec);
+        let error = sorter
+            .sort(Cursor::new(vec![0, 0, 0, 1, 0, 0]), Vec::new())
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -1,6 +1,8 @@
-use std::cmp::Ord;
+use std::cmp::{Ord, Ordering};
 
-use num::range_inclusive;
+use crate::sort::sorter::Sorter;
+use crate::sort::stats;
+use crate::sort::trace;
 
 /// O(n log n) time (worst)\
 /// O(n) time (best)\
@@ -10,38 +12,86 @@ use num::range_inclusive;
 pub fn heap_sort<T>(array: &mut [T])
 where
     T: Ord,
+{
+    heap_sort_by(array, T::cmp);
+}
+
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(1) space\
+/// Not stable
+pub fn heap_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let size = array.len();
 
-    for i in range_inclusive(0, size / 2 - 1).rev() {
-        heapify(array, size, i);
+    for i in (0..size / 2).rev() {
+        heapify(array, size, i, &mut compare);
     }
 
-    for i in range_inclusive(0, size - 1).rev() {
+    for i in (1..size).rev() {
         array.swap(0, i);
-        heapify(array, i, 0);
+        stats::swap();
+        trace::swap(array, 0, i);
+        heapify(array, i, 0, &mut compare);
     }
 }
 
-fn heapify<T>(array: &mut [T], size: usize, index: usize)
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(1) space\
+/// Not stable
+pub fn heap_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
 where
-    T: Ord,
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    heap_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// `heap_sort` as a `Sorter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeapSort;
+
+impl Sorter for HeapSort {
+    const NAME: &'static str = "heap_sort";
+    const STABLE: bool = false;
+    const IN_PLACE: bool = true;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        heap_sort_by(array, compare);
+    }
+}
+
+pub(crate) fn heapify<T, F>(array: &mut [T], size: usize, index: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
+    let _depth = stats::enter();
+    trace::heapify(array, index, size);
     let mut largest = index;
     let left = index * 2 + 1;
     let right = index * 2 + 2;
 
-    if left < size && array[left] > array[largest] {
+    if left < size && compare(&array[left], &array[largest]) == Ordering::Greater {
         largest = left;
     }
 
-    if right < size && array[right] > array[largest] {
+    if right < size && compare(&array[right], &array[largest]) == Ordering::Greater {
         largest = right;
     }
 
     if largest != index {
         array.swap(index, largest);
-        heapify(array, size, largest);
+        stats::swap();
+        trace::swap(array, index, largest);
+        heapify(array, size, largest, compare);
     }
 }
 
@@ -64,4 +114,20 @@ mod tests {
 
         assert_eq!(array, ["a", "aa", "bab", "bac", "bac", "bbc", "ca"]);
     }
+
+    #[test]
+    fn heap_sort_by_floats_test_1() {
+        let mut array = [2.5f64, -1.0, 0.0, 13.25, -7.5];
+        heap_sort_by(&mut array, |a, b| b.total_cmp(a));
+
+        assert_eq!(array, [13.25, 2.5, 0.0, -1.0, -7.5]);
+    }
+
+    #[test]
+    fn heap_sort_by_key_test_1() {
+        let mut array = ["ccc", "a", "bb", "dddd", ""];
+        heap_sort_by_key(&mut array, |string| string.len());
+
+        assert_eq!(array, ["", "a", "bb", "ccc", "dddd"]);
+    }
 }
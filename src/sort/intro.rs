@@ -0,0 +1,192 @@
+use std::cmp::{Ord, Ordering};
+
+use crate::sort::heap::heap_sort_by;
+use crate::sort::network::small_sort;
+use crate::sort::quick::find_partition;
+use crate::sort::sorter::Sorter;
+use crate::sort::stats;
+
+static INSERTION_THRESHOLD: usize = 16;
+static NINTHER_THRESHOLD: usize = 128;
+
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn intro_sort<T>(array: &mut [T])
+where
+    T: Ord,
+{
+    intro_sort_by(array, T::cmp);
+}
+
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn intro_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let limit = 2 * (usize::BITS - array.len().leading_zeros()) as usize;
+    intro_sort_helper(array, limit, &mut compare);
+}
+
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn intro_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    intro_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// `intro_sort` as a `Sorter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IntroSort;
+
+impl Sorter for IntroSort {
+    const NAME: &'static str = "intro_sort";
+    const STABLE: bool = false;
+    const IN_PLACE: bool = true;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        intro_sort_by(array, compare);
+    }
+}
+
+fn intro_sort_helper<T, F>(mut array: &mut [T], mut limit: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let _depth = stats::enter();
+
+    loop {
+        if array.len() <= INSERTION_THRESHOLD {
+            small_sort(array, compare);
+            return;
+        }
+
+        // Too many bad partitions, quicksort is going quadratic
+        if limit == 0 {
+            heap_sort_by(array, &mut *compare);
+            return;
+        }
+
+        limit -= 1;
+
+        let pivot = choose_pivot(array, compare);
+        let (less, greater) = find_partition(array, pivot, compare);
+
+        let (left, rest) = array.split_at_mut(less);
+        let right = &mut rest[(greater - less)..];
+
+        if left.len() < right.len() {
+            intro_sort_helper(left, limit, compare);
+            array = right;
+        } else {
+            intro_sort_helper(right, limit, compare);
+            array = left;
+        }
+    }
+}
+
+/// Median of three for short ranges, Tukey's ninther for long ones.
+fn choose_pivot<T, F>(array: &[T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let size = array.len();
+    let mid = size / 2;
+
+    if size < NINTHER_THRESHOLD {
+        return median_of_three(array, 0, mid, size - 1, compare);
+    }
+
+    let step = size / 8;
+    let first = median_of_three(array, 0, step, 2 * step, compare);
+    let second = median_of_three(array, mid - step, mid, mid + step, compare);
+    let third = median_of_three(
+        array,
+        size - 1 - 2 * step,
+        size - 1 - step,
+        size - 1,
+        compare,
+    );
+
+    median_of_three(array, first, second, third, compare)
+}
+
+fn median_of_three<T, F>(array: &[T], a: usize, b: usize, c: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let less =
+        |compare: &mut F, x: usize, y: usize| compare(&array[x], &array[y]) == Ordering::Less;
+
+    if less(compare, a, b) {
+        if less(compare, b, c) {
+            b
+        } else if less(compare, a, c) {
+            c
+        } else {
+            a
+        }
+    } else if less(compare, a, c) {
+        a
+    } else if less(compare, b, c) {
+        c
+    } else {
+        b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intro_sort_numbers_test_1() {
+        let mut array = [5, 1, 2, 345, 35, 12, 1];
+        intro_sort(&mut array);
+
+        assert_eq!(array, [1, 1, 2, 5, 12, 35, 345]);
+    }
+
+    #[test]
+    fn intro_sort_string_test_1() {
+        let mut array = ["aa", "bbc", "bab", "ca", "bac", "bac", "a"];
+        intro_sort(&mut array);
+
+        assert_eq!(array, ["a", "aa", "bab", "bac", "bac", "bbc", "ca"]);
+    }
+
+    #[test]
+    fn intro_sort_organ_pipe_test_1() {
+        let mut array: Vec<i32> = (0..500).chain((0..500).rev()).collect();
+        let mut expected = array.clone();
+        expected.sort();
+        intro_sort(&mut array);
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn intro_sort_heap_fallback_test_1() {
+        let mut array: Vec<i32> = (0..200).map(|i| (i * 37) % 101).collect();
+        let mut expected = array.clone();
+        expected.sort();
+        intro_sort_helper(&mut array, 0, &mut i32::cmp);
+
+        assert_eq!(array, expected);
+    }
+}
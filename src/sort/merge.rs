@@ -1,62 +1,590 @@
-use std::cmp::{Ord, Ordering};
+use std::cmp::{min, Ord, Ordering};
+use std::{mem, ptr};
+
+use crate::sort::network::{binary_insertion_sort_helper, insertion_sort_helper};
+use crate::sort::sorter::Sorter;
+use crate::sort::stats;
+use crate::sort::trace;
+
+static INSERTION_THRESHOLD: usize = 16;
+static MIN_MERGE: usize = 64;
+static MIN_GALLOP: usize = 7;
 
 /// O(n log n) time (worst)\
-/// O(n log n) time (best)\
+/// O(n) time (best)\
 /// O(n log n) time (average)\
 /// O(n) space\
 /// Stable
 pub fn merge_sort<T>(array: &mut [T])
 where
-    T: Ord + Default + Copy,
+    T: Ord,
 {
-    merge_sort_helper(array, 0, array.len() - 1);
+    merge_sort_by(array, T::cmp);
 }
 
-fn merge_sort_helper<T>(array: &mut [T], left: usize, right: usize)
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(n) space\
+/// Stable
+pub fn merge_sort_by<T, F>(array: &mut [T], mut compare: F)
 where
-    T: Ord + Default + Copy,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    if left < right {
-        let mid = (left + right) / 2;
-        merge_sort_helper(array, left, mid);
-        merge_sort_helper(array, mid + 1, right);
-        merge(array, left, mid, right);
+    // Zero sized values are indistinguishable from each other
+    if mem::size_of::<T>() == 0 || array.len() < 2 {
+        return;
     }
+
+    // A single scratch buffer large enough for the first half of any merge
+    let mut buffer = Vec::with_capacity(array.len() / 2);
+    merge_sort_helper(array, &mut buffer, &mut compare);
 }
 
-fn merge<T>(array: &mut [T], left: usize, mid: usize, right: usize)
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(n) space\
+/// Stable
+pub fn merge_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
 where
-    T: Ord + Default + Copy,
+    F: FnMut(&T) -> K,
+    K: Ord,
 {
-    let mut first_iter = 0;
-    let mut second_iter = 0;
-    let size = right - left + 1;
-    let mut result = vec![T::default(); size];
-
-    while left + first_iter <= mid && mid + 1 + second_iter <= right {
-        match array[left + first_iter].cmp(&array[mid + 1 + second_iter]) {
-            Ordering::Less => {
-                result[first_iter + second_iter] = array[left + first_iter];
-                first_iter += 1;
-            }
-            _ => {
-                result[first_iter + second_iter] = array[mid + 1 + second_iter];
-                second_iter += 1;
-            }
+    merge_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// `merge_sort` as a `Sorter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeSort;
+
+impl Sorter for MergeSort {
+    const NAME: &'static str = "merge_sort";
+    const STABLE: bool = true;
+    const IN_PLACE: bool = false;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        merge_sort_by(array, compare);
+    }
+}
+
+fn merge_sort_helper<T, F>(array: &mut [T], buffer: &mut Vec<T>, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let _depth = stats::enter();
+    let size = array.len();
+
+    if size <= INSERTION_THRESHOLD {
+        insertion_sort_helper(array, compare);
+        return;
+    }
+
+    let mid = size / 2;
+    merge_sort_helper(&mut array[..mid], buffer, compare);
+    merge_sort_helper(&mut array[mid..], buffer, compare);
+    merge(array, mid, buffer, compare);
+}
+
+/// Merges the sorted runs `array[..mid]` and `array[mid..]`.
+/// The first run is moved out into the spare capacity of `buffer`,
+/// which must be able to hold `mid` elements.
+fn merge<T, F>(array: &mut [T], mid: usize, buffer: &mut Vec<T>, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let size = array.len();
+    assert!(mid <= buffer.capacity() && buffer.is_empty());
+
+    // The runs are already in order
+    if compare(&array[mid - 1], &array[mid]) != Ordering::Greater {
+        return;
+    }
+
+    // SAFETY: the first run is bitwise moved into `buffer`, leaving holes
+    // in `array`. Every step fills the leftmost hole with exactly one value,
+    // either from `buffer` or from the second run, which never lies to the
+    // left of the holes. `MergeHole` moves the rest of `buffer` into the
+    // remaining holes when dropped, even if `compare` panics, so every value
+    // ends up in `array` exactly once and `buffer` never owns any of them.
+    unsafe {
+        let base = array.as_mut_ptr();
+        let scratch = buffer.as_mut_ptr();
+        ptr::copy_nonoverlapping(base, scratch, mid);
+        stats::moves(mid);
+        trace::buffer(scratch, mid);
+        trace::write(scratch, base, mid);
+
+        let mut hole = MergeHole {
+            start: scratch,
+            end: scratch.add(mid),
+            dest: base,
+        };
+
+        let mut right = base.add(mid);
+        let end = base.add(size);
+
+        while hole.start < hole.end && right < end {
+            // Ties go to the first run to keep the sort stable
+            let source = if compare(&*right, &*hole.start) == Ordering::Less {
+                right = right.add(1);
+                right.sub(1)
+            } else {
+                hole.start = hole.start.add(1);
+                hole.start.sub(1)
+            };
+
+            ptr::copy_nonoverlapping(source, hole.dest, 1);
+            stats::moves(1);
+            trace::write(hole.dest, source, 1);
+            hole.dest = hole.dest.add(1);
         }
+
+        // What is left in the buffer is moved back when `hole` is dropped
+        let remaining = hole.end.offset_from(hole.start) as usize;
+        trace::write(hole.dest, hole.start, remaining);
     }
+}
+
+/// Values in `start..end` are moved to `dest` on drop.
+struct MergeHole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
 
-    while left + first_iter <= mid {
-        result[first_iter + second_iter] = array[left + first_iter];
-        first_iter += 1;
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        // SAFETY: `start..end` is a valid run of values inside the scratch
+        // buffer and `dest` points to at least as many holes in the array
+        unsafe {
+            let count = self.end.offset_from(self.start) as usize;
+            ptr::copy_nonoverlapping(self.start, self.dest, count);
+            stats::moves(count);
+        }
     }
+}
 
-    while mid + 1 + second_iter <= right {
-        result[first_iter + second_iter] = array[mid + 1 + second_iter];
-        second_iter += 1;
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(n) space\
+/// Stable
+pub fn tim_sort<T>(array: &mut [T])
+where
+    T: Ord,
+{
+    tim_sort_by(array, T::cmp);
+}
+
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(n) space\
+/// Stable
+pub fn tim_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let size = array.len();
+
+    // Zero sized values are indistinguishable from each other
+    if mem::size_of::<T>() == 0 || size < 2 {
+        return;
+    }
+
+    let compare = &mut compare;
+    let min_run = min_run_length(size);
+    let mut buffer = Vec::with_capacity(size / 2);
+    let mut runs = Vec::new();
+    let mut min_gallop = MIN_GALLOP;
+    let mut start = 0;
+
+    while start < size {
+        let mut len = count_run(&mut array[start..], compare);
+
+        // Extend short runs to `min_run` elements
+        if len < min_run {
+            let end = min(size, start + min_run);
+            binary_insertion_sort_helper(&mut array[start..end], len, compare);
+            len = end - start;
+        }
+
+        runs.push(Run { start, len });
+        start += len;
+
+        while let Some(index) = collapse(&runs, start == size) {
+            let Run { start, len: first } = runs[index];
+            let second = runs.remove(index + 1).len;
+            runs[index].len += second;
+
+            let range = start..(start + first + second);
+            merge_runs(
+                &mut array[range],
+                first,
+                &mut buffer,
+                &mut min_gallop,
+                compare,
+            );
+        }
     }
+}
 
-    array[left..(left + size)].copy_from_slice(&result);
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(n) space\
+/// Stable
+pub fn tim_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    tim_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// `tim_sort` as a `Sorter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimSort;
+
+impl Sorter for TimSort {
+    const NAME: &'static str = "tim_sort";
+    const STABLE: bool = true;
+    const IN_PLACE: bool = false;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        tim_sort_by(array, compare);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+/// Returns `n` itself for short arrays, otherwise a value in `MIN_MERGE / 2..=MIN_MERGE`
+/// such that `n / min_run` is a power of two or slightly less than one.
+fn min_run_length(mut size: usize) -> usize {
+    let mut remainder = 0;
+
+    while size >= MIN_MERGE {
+        remainder |= size & 1;
+        size >>= 1;
+    }
+
+    size + remainder
+}
+
+/// Length of the run at the start of `array`. Strictly descending
+/// runs are reversed, which keeps equal elements in order.
+fn count_run<T, F>(array: &mut [T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let size = array.len();
+
+    if size < 2 {
+        return size;
+    }
+
+    let mut end = 2;
+
+    if compare(&array[1], &array[0]) == Ordering::Less {
+        while end < size && compare(&array[end], &array[end - 1]) == Ordering::Less {
+            end += 1;
+        }
+
+        array[..end].reverse();
+        stats::swaps(end / 2);
+    } else {
+        while end < size && compare(&array[end], &array[end - 1]) != Ordering::Less {
+            end += 1;
+        }
+    }
+
+    end
+}
+
+/// Picks the pair of runs `index` and `index + 1` to merge next,
+/// or `None` if the run stack invariants already hold:
+/// `len[n - 3] > len[n - 2] + len[n - 1]` and `len[n - 2] > len[n - 1]`.
+fn collapse(runs: &[Run], finished: bool) -> Option<usize> {
+    let n = runs.len();
+
+    if n >= 2
+        && (finished
+            || runs[n - 2].len <= runs[n - 1].len
+            || (n >= 3 && runs[n - 3].len <= runs[n - 2].len + runs[n - 1].len)
+            || (n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len))
+    {
+        if n >= 3 && runs[n - 3].len < runs[n - 1].len {
+            Some(n - 3)
+        } else {
+            Some(n - 2)
+        }
+    } else {
+        None
+    }
+}
+
+/// Exponential search followed by a binary search.
+/// Returns the number of leading indices `0..size` satisfying `predicate`,
+/// which must hold for a prefix of them and fail for the rest.
+fn gallop<P>(size: usize, mut predicate: P) -> usize
+where
+    P: FnMut(usize) -> bool,
+{
+    let mut low = 0;
+    let mut high = 0;
+
+    while high < size && predicate(high) {
+        low = high + 1;
+        high = high * 2 + 1;
+    }
+
+    high = min(high, size);
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if predicate(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    low
+}
+
+/// Merges the sorted runs `array[..mid]` and `array[mid..]`,
+/// moving the shorter one out into `buffer`.
+fn merge_runs<T, F>(
+    array: &mut [T],
+    mid: usize,
+    buffer: &mut Vec<T>,
+    min_gallop: &mut usize,
+    compare: &mut F,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // Elements of the first run not greater than the head of the second are in place
+    let skip = gallop(mid, |i| compare(&array[mid], &array[i]) != Ordering::Less);
+    let array = &mut array[skip..];
+    let mid = mid - skip;
+
+    if mid == 0 {
+        return;
+    }
+
+    // Elements of the second run not less than the tail of the first are in place
+    let tail = array.len() - mid;
+    let keep = gallop(tail, |i| {
+        compare(&array[mid + i], &array[mid - 1]) == Ordering::Less
+    });
+    let array = &mut array[..(mid + keep)];
+
+    assert!(min(mid, keep) <= buffer.capacity() && buffer.is_empty());
+
+    if mid <= keep {
+        merge_low(array, mid, buffer, min_gallop, compare);
+    } else {
+        merge_high(array, mid, buffer, min_gallop, compare);
+    }
+}
+
+/// Front to back merge with the first run moved into `buffer`.
+fn merge_low<T, F>(
+    array: &mut [T],
+    mid: usize,
+    buffer: &mut Vec<T>,
+    min_gallop: &mut usize,
+    compare: &mut F,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // SAFETY: same scheme as in `merge`. The holes always lie between
+    // `hole.dest` and `right` and there are exactly as many of them as values
+    // left in the buffer, so every copy below writes into holes only and
+    // `MergeHole` fills the rest when dropped.
+    unsafe {
+        let base = array.as_mut_ptr();
+        let scratch = buffer.as_mut_ptr();
+        ptr::copy_nonoverlapping(base, scratch, mid);
+        stats::moves(mid);
+
+        let mut hole = MergeHole {
+            start: scratch,
+            end: scratch.add(mid),
+            dest: base,
+        };
+
+        let mut right = base.add(mid);
+        let end = base.add(array.len());
+
+        'outer: while hole.start < hole.end && right < end {
+            let mut first_wins = 0;
+            let mut second_wins = 0;
+
+            // One value at a time until one run keeps winning
+            while first_wins.max(second_wins) < *min_gallop {
+                if compare(&*right, &*hole.start) == Ordering::Less {
+                    ptr::copy_nonoverlapping(right, hole.dest, 1);
+                    right = right.add(1);
+                    second_wins += 1;
+                    first_wins = 0;
+                } else {
+                    ptr::copy_nonoverlapping(hole.start, hole.dest, 1);
+                    hole.start = hole.start.add(1);
+                    first_wins += 1;
+                    second_wins = 0;
+                }
+
+                stats::moves(1);
+                hole.dest = hole.dest.add(1);
+
+                if hole.start == hole.end || right == end {
+                    break 'outer;
+                }
+            }
+
+            // Galloping mode, copy whole stretches at once
+            loop {
+                let remaining = hole.end.offset_from(hole.start) as usize;
+                let first_count = gallop(remaining, |i| {
+                    compare(&*right, &*hole.start.add(i)) != Ordering::Less
+                });
+                ptr::copy_nonoverlapping(hole.start, hole.dest, first_count);
+                stats::moves(first_count);
+                hole.start = hole.start.add(first_count);
+                hole.dest = hole.dest.add(first_count);
+
+                if hole.start == hole.end {
+                    break 'outer;
+                }
+
+                let remaining = end.offset_from(right) as usize;
+                let second_count = gallop(remaining, |i| {
+                    compare(&*right.add(i), &*hole.start) == Ordering::Less
+                });
+                ptr::copy(right, hole.dest, second_count);
+                stats::moves(second_count);
+                right = right.add(second_count);
+                hole.dest = hole.dest.add(second_count);
+
+                if right == end {
+                    break 'outer;
+                }
+
+                if first_count < MIN_GALLOP && second_count < MIN_GALLOP {
+                    *min_gallop += 1;
+                    break;
+                }
+
+                *min_gallop = min_gallop.saturating_sub(1).max(1);
+            }
+        }
+    }
+}
+
+/// Back to front merge with the second run moved into `buffer`.
+fn merge_high<T, F>(
+    array: &mut [T],
+    mid: usize,
+    buffer: &mut Vec<T>,
+    min_gallop: &mut usize,
+    compare: &mut F,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // SAFETY: mirror image of `merge_low`. The holes always lie between
+    // `hole.dest`, the end of what is left of the first run, and `out`,
+    // and there are exactly as many of them as values left in the buffer.
+    unsafe {
+        let base = array.as_mut_ptr();
+        let scratch = buffer.as_mut_ptr();
+        let second = array.len() - mid;
+        ptr::copy_nonoverlapping(base.add(mid), scratch, second);
+        stats::moves(second);
+
+        let mut hole = MergeHole {
+            start: scratch,
+            end: scratch.add(second),
+            dest: base.add(mid),
+        };
+
+        let mut out = base.add(array.len());
+
+        'outer: while hole.start < hole.end && base < hole.dest {
+            let mut first_wins = 0;
+            let mut second_wins = 0;
+
+            while first_wins.max(second_wins) < *min_gallop {
+                out = out.sub(1);
+
+                // Ties go to the second run, it comes last
+                if compare(&*hole.end.sub(1), &*hole.dest.sub(1)) == Ordering::Less {
+                    hole.dest = hole.dest.sub(1);
+                    ptr::copy_nonoverlapping(hole.dest, out, 1);
+                    first_wins += 1;
+                    second_wins = 0;
+                } else {
+                    hole.end = hole.end.sub(1);
+                    ptr::copy_nonoverlapping(hole.end, out, 1);
+                    second_wins += 1;
+                    first_wins = 0;
+                }
+
+                stats::moves(1);
+
+                if hole.start == hole.end || base == hole.dest {
+                    break 'outer;
+                }
+            }
+
+            loop {
+                let remaining = hole.dest.offset_from(base) as usize;
+                let first_count = gallop(remaining, |i| {
+                    compare(&*hole.end.sub(1), &*hole.dest.sub(i + 1)) == Ordering::Less
+                });
+                out = out.sub(first_count);
+                hole.dest = hole.dest.sub(first_count);
+                ptr::copy(hole.dest, out, first_count);
+                stats::moves(first_count);
+
+                if base == hole.dest {
+                    break 'outer;
+                }
+
+                let remaining = hole.end.offset_from(hole.start) as usize;
+                let second_count = gallop(remaining, |i| {
+                    compare(&*hole.end.sub(i + 1), &*hole.dest.sub(1)) != Ordering::Less
+                });
+                out = out.sub(second_count);
+                hole.end = hole.end.sub(second_count);
+                ptr::copy_nonoverlapping(hole.end, out, second_count);
+                stats::moves(second_count);
+
+                if hole.start == hole.end {
+                    break 'outer;
+                }
+
+                if first_count < MIN_GALLOP && second_count < MIN_GALLOP {
+                    *min_gallop += 1;
+                    break;
+                }
+
+                *min_gallop = min_gallop.saturating_sub(1).max(1);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -78,4 +606,113 @@ mod tests {
 
         assert_eq!(array, ["a", "aa", "bab", "bac", "bac", "bbc", "ca"]);
     }
+
+    #[test]
+    fn merge_sort_by_floats_test_1() {
+        let mut array = [2.5f64, -1.0, 0.0, 13.25, -7.5];
+        merge_sort_by(&mut array, |a, b| b.total_cmp(a));
+
+        assert_eq!(array, [13.25, 2.5, 0.0, -1.0, -7.5]);
+    }
+
+    #[test]
+    fn merge_sort_by_key_stable_test_1() {
+        let mut array = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e'), (2, 'f')];
+        merge_sort_by_key(&mut array, |pair| pair.0);
+
+        assert_eq!(
+            array,
+            [(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c'), (2, 'f')]
+        );
+    }
+
+    #[test]
+    fn merge_sort_owned_test_1() {
+        let mut array: Vec<String> = ["aa", "bbc", "bab", "ca", "bac", "bac", "a"]
+            .iter()
+            .map(|string| string.to_string())
+            .collect();
+        merge_sort(&mut array);
+
+        assert_eq!(array, ["a", "aa", "bab", "bac", "bac", "bbc", "ca"]);
+    }
+
+    #[test]
+    fn merge_sort_panic_safety_test_1() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut array: Vec<(i32, Rc<()>)> = (0..40)
+            .map(|i| (i * 7919 % 41, Rc::clone(&counter)))
+            .collect();
+
+        // The insertion sorted chunks take 104 comparisons,
+        // so this panics in the middle of the last merge
+        let mut comparisons = 0;
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            merge_sort_by(&mut array, |a, b| {
+                comparisons += 1;
+                assert!(comparisons < 160);
+                a.0.cmp(&b.0)
+            });
+        }));
+
+        assert!(result.is_err());
+
+        let mut values: Vec<i32> = array.iter().map(|pair| pair.0).collect();
+        values.sort();
+
+        assert!(values.into_iter().eq((0..41).filter(|&value| value != 35)));
+        assert_eq!(Rc::strong_count(&counter), 41);
+
+        drop(array);
+
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn tim_sort_numbers_test_1() {
+        let mut array = [5, 1, 2, 345, 35, 12, 1];
+        tim_sort(&mut array);
+
+        assert_eq!(array, [1, 1, 2, 5, 12, 35, 345]);
+    }
+
+    #[test]
+    fn tim_sort_runs_test_1() {
+        let mut array: Vec<i32> = (0..300)
+            .chain((100..400).rev())
+            .chain(50..250)
+            .chain((0..700).map(|i| i * 7919 % 1000))
+            .collect();
+        let mut expected = array.clone();
+        expected.sort();
+        tim_sort(&mut array);
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn tim_sort_by_key_stable_test_1() {
+        let mut array: Vec<(usize, usize)> = (0..1000).map(|i| (i * 7919 % 13, i)).collect();
+        let mut expected = array.clone();
+        expected.sort_by_key(|pair| pair.0);
+        tim_sort_by_key(&mut array, |pair| pair.0);
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn tim_sort_presorted_test_1() {
+        let mut comparisons = 0;
+        let mut array: Vec<i32> = (0..1000).rev().collect();
+        tim_sort_by(&mut array, |a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+
+        assert!(array.iter().copied().eq(0..1000));
+        assert_eq!(comparisons, 999);
+    }
 }
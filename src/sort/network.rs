@@ -0,0 +1,394 @@
+use std::cmp::{Ord, Ordering};
+
+use crate::sort::sorter::Sorter;
+use crate::sort::stats;
+use crate::sort::trace;
+
+/// Longest slice `small_sort` hands to a sorting network.
+static NETWORK_THRESHOLD: usize = 8;
+
+// Size optimal networks, as lists of compare-exchange pairs
+static NETWORK_2: &[(usize, usize)] = &[(0, 1)];
+static NETWORK_3: &[(usize, usize)] = &[(0, 2), (0, 1), (1, 2)];
+static NETWORK_4: &[(usize, usize)] = &[(0, 2), (1, 3), (0, 1), (2, 3), (1, 2)];
+static NETWORK_5: &[(usize, usize)] = &[
+    (0, 3),
+    (1, 4),
+    (0, 2),
+    (1, 3),
+    (0, 1),
+    (2, 4),
+    (1, 2),
+    (3, 4),
+    (2, 3),
+];
+static NETWORK_6: &[(usize, usize)] = &[
+    (0, 5),
+    (1, 3),
+    (2, 4),
+    (1, 2),
+    (3, 4),
+    (0, 3),
+    (2, 5),
+    (0, 1),
+    (2, 3),
+    (4, 5),
+    (1, 2),
+    (3, 4),
+];
+static NETWORK_7: &[(usize, usize)] = &[
+    (0, 6),
+    (2, 3),
+    (4, 5),
+    (0, 2),
+    (1, 4),
+    (3, 6),
+    (0, 1),
+    (2, 5),
+    (3, 4),
+    (1, 2),
+    (4, 6),
+    (2, 3),
+    (4, 5),
+    (1, 2),
+    (3, 4),
+    (5, 6),
+];
+static NETWORK_8: &[(usize, usize)] = &[
+    (0, 2),
+    (1, 3),
+    (4, 6),
+    (5, 7),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+    (0, 1),
+    (2, 3),
+    (4, 5),
+    (6, 7),
+    (2, 4),
+    (3, 5),
+    (1, 4),
+    (3, 6),
+    (1, 2),
+    (3, 4),
+    (5, 6),
+];
+
+/// Size optimal networks up to 8 elements,
+/// Batcher's odd-even merge sort for longer arrays.\
+/// O(n log^2 n) time\
+/// O(1) space\
+/// Not stable
+pub fn network_sort<T, const N: usize>(array: &mut [T; N])
+where
+    T: Ord,
+{
+    network_sort_by(array, T::cmp);
+}
+
+/// Size optimal networks up to 8 elements,
+/// Batcher's odd-even merge sort for longer arrays.\
+/// O(n log^2 n) time\
+/// O(1) space\
+/// Not stable
+pub fn network_sort_by<T, F, const N: usize>(array: &mut [T; N], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    network_sort_helper(array, &mut compare);
+}
+
+/// Size optimal networks up to 8 elements,
+/// Batcher's odd-even merge sort for longer arrays.\
+/// O(n log^2 n) time\
+/// O(1) space\
+/// Not stable
+pub fn network_sort_by_key<T, K, F, const N: usize>(array: &mut [T; N], mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    network_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// O(n^2) time (worst)\
+/// O(n) time (best)\
+/// O(n^2) time (average)\
+/// O(1) space\
+/// Stable
+pub fn insertion_sort<T>(array: &mut [T])
+where
+    T: Ord,
+{
+    insertion_sort_by(array, T::cmp);
+}
+
+/// O(n^2) time (worst)\
+/// O(n) time (best)\
+/// O(n^2) time (average)\
+/// O(1) space\
+/// Stable
+pub fn insertion_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    insertion_sort_helper(array, &mut compare);
+}
+
+/// O(n^2) time (worst)\
+/// O(n) time (best)\
+/// O(n^2) time (average)\
+/// O(1) space\
+/// Stable
+pub fn insertion_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    insertion_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// `insertion_sort` as a `Sorter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InsertionSort;
+
+impl Sorter for InsertionSort {
+    const NAME: &'static str = "insertion_sort";
+    const STABLE: bool = true;
+    const IN_PLACE: bool = true;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        insertion_sort_by(array, compare);
+    }
+}
+
+/// O(n log n) comparisons, O(n^2) moves (worst)\
+/// O(n log n) comparisons, O(n) moves (best)\
+/// O(n log n) comparisons, O(n^2) moves (average)\
+/// O(1) space\
+/// Stable
+pub fn binary_insertion_sort<T>(array: &mut [T])
+where
+    T: Ord,
+{
+    binary_insertion_sort_by(array, T::cmp);
+}
+
+/// O(n log n) comparisons, O(n^2) moves (worst)\
+/// O(n log n) comparisons, O(n) moves (best)\
+/// O(n log n) comparisons, O(n^2) moves (average)\
+/// O(1) space\
+/// Stable
+pub fn binary_insertion_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    binary_insertion_sort_helper(array, 0, &mut compare);
+}
+
+/// O(n log n) comparisons, O(n^2) moves (worst)\
+/// O(n log n) comparisons, O(n) moves (best)\
+/// O(n log n) comparisons, O(n^2) moves (average)\
+/// O(1) space\
+/// Stable
+pub fn binary_insertion_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    binary_insertion_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// `binary_insertion_sort` as a `Sorter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinaryInsertionSort;
+
+impl Sorter for BinaryInsertionSort {
+    const NAME: &'static str = "binary_insertion_sort";
+    const STABLE: bool = true;
+    const IN_PLACE: bool = true;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        binary_insertion_sort_by(array, compare);
+    }
+}
+
+/// Base case of the unstable sorts: a sorting network
+/// for up to 8 elements, insertion sort otherwise.
+pub(crate) fn small_sort<T, F>(array: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if array.len() <= NETWORK_THRESHOLD {
+        network_sort_helper(array, compare);
+    } else {
+        insertion_sort_helper(array, compare);
+    }
+}
+
+pub(crate) fn insertion_sort_helper<T, F>(array: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..array.len() {
+        let mut j = i;
+
+        while j > 0 && compare(&array[j - 1], &array[j]) == Ordering::Greater {
+            array.swap(j - 1, j);
+            stats::swap();
+            trace::swap(array, j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+/// Sorts `array` given that `array[..sorted]` is already sorted.
+pub(crate) fn binary_insertion_sort_helper<T, F>(array: &mut [T], sorted: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in sorted.max(1)..array.len() {
+        let (prefix, rest) = array.split_at(i);
+        let position = prefix.partition_point(|item| compare(&rest[0], item) != Ordering::Less);
+        array[position..=i].rotate_right(1);
+        stats::moves(i - position + 1);
+    }
+}
+
+fn network_sort_helper<T, F>(array: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let network = match array.len() {
+        0 | 1 => return,
+        2 => NETWORK_2,
+        3 => NETWORK_3,
+        4 => NETWORK_4,
+        5 => NETWORK_5,
+        6 => NETWORK_6,
+        7 => NETWORK_7,
+        8 => NETWORK_8,
+        _ => {
+            batcher_sort(array, compare);
+            return;
+        }
+    };
+
+    for &(i, j) in network {
+        compare_exchange(array, i, j, compare);
+    }
+}
+
+/// Batcher's odd-even merge sort for any length. Comparators that would
+/// touch the padding up to the next power of two are skipped.
+fn batcher_sort<T, F>(array: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let size = array.len();
+    let mut p = 1;
+
+    while p < size {
+        let mut k = p;
+
+        while k > 0 {
+            let mut j = k % p;
+
+            while j + k < size {
+                for i in 0..k.min(size - j - k) {
+                    if (i + j) / (2 * p) == (i + j + k) / (2 * p) {
+                        compare_exchange(array, i + j, i + j + k, compare);
+                    }
+                }
+
+                j += 2 * k;
+            }
+
+            k /= 2;
+        }
+
+        p *= 2;
+    }
+}
+
+/// Puts `array[i]` and `array[j]` in order, `i < j`.
+fn compare_exchange<T, F>(array: &mut [T], i: usize, j: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if compare(&array[j], &array[i]) == Ordering::Less {
+        array.swap(i, j);
+        stats::swap();
+        trace::swap(array, i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// By the 0-1 principle a network sorts everything
+    /// if it sorts every sequence of zeros and ones.
+    fn sorts_all_binary<const N: usize>() -> bool {
+        (0..1u32 << N).all(|bits| {
+            let mut array = [0u8; N];
+
+            for (index, item) in array.iter_mut().enumerate() {
+                *item = (bits >> index & 1) as u8;
+            }
+
+            network_sort(&mut array);
+            array.windows(2).all(|pair| pair[0] <= pair[1])
+        })
+    }
+
+    #[test]
+    fn network_sort_zero_one_test_1() {
+        assert!(sorts_all_binary::<2>());
+        assert!(sorts_all_binary::<3>());
+        assert!(sorts_all_binary::<4>());
+        assert!(sorts_all_binary::<5>());
+        assert!(sorts_all_binary::<6>());
+        assert!(sorts_all_binary::<7>());
+        assert!(sorts_all_binary::<8>());
+        assert!(sorts_all_binary::<11>());
+        assert!(sorts_all_binary::<16>());
+    }
+
+    #[test]
+    fn network_sort_string_test_1() {
+        let mut array = ["aa", "bbc", "bab", "ca", "bac", "bac", "a"];
+        network_sort(&mut array);
+
+        assert_eq!(array, ["a", "aa", "bab", "bac", "bac", "bbc", "ca"]);
+
+        let mut array: [u32; 32] = std::array::from_fn(|i| i as u32 * 7919 % 101);
+        let mut expected = array;
+        expected.sort();
+        network_sort(&mut array);
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn insertion_sort_by_key_stable_test_1() {
+        let mut array: Vec<(usize, usize)> = (0..100).map(|i| (i * 7919 % 13, i)).collect();
+        let mut expected = array.clone();
+        expected.sort_by_key(|pair| pair.0);
+
+        let mut binary = array.clone();
+        insertion_sort_by_key(&mut array, |pair| pair.0);
+        binary_insertion_sort_by_key(&mut binary, |pair| pair.0);
+
+        assert_eq!(array, expected);
+        assert_eq!(binary, expected);
+    }
+}
@@ -0,0 +1,397 @@
+use std::cmp::{Ord, Ordering};
+use std::mem::{self, MaybeUninit};
+use std::{ptr, thread};
+
+use crate::sort::merge::merge_sort_by;
+use crate::sort::quick::{find_partition, find_pivot, quick_sort_helper};
+use crate::sort::stats;
+
+static SEQUENTIAL_THRESHOLD: usize = 1 << 13;
+
+/// O(n log n) work (worst)\
+/// O(n) work (best)\
+/// O(n log n) work (average)\
+/// O(n) space\
+/// Stable, same result as `merge_sort`
+pub fn par_merge_sort<T>(array: &mut [T])
+where
+    T: Ord + Send,
+{
+    par_merge_sort_by(array, T::cmp);
+}
+
+/// O(n log n) work (worst)\
+/// O(n) work (best)\
+/// O(n log n) work (average)\
+/// O(n) space\
+/// Stable, same result as `merge_sort_by`
+pub fn par_merge_sort_by<T, F>(array: &mut [T], compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    // Zero sized values are indistinguishable from each other
+    if mem::size_of::<T>() == 0 || array.len() < 2 {
+        return;
+    }
+
+    let size = array.len();
+    let mut buffer: Vec<T> = Vec::with_capacity(size);
+    let scratch = &mut buffer.spare_capacity_mut()[..size];
+
+    par_merge_sort_helper(array, scratch, thread_depth(), &compare);
+}
+
+/// O(n log n) work (worst)\
+/// O(n) work (best)\
+/// O(n log n) work (average)\
+/// O(n) space\
+/// Stable, same result as `merge_sort_by_key`
+pub fn par_merge_sort_by_key<T, K, F>(array: &mut [T], key: F)
+where
+    T: Send,
+    F: Fn(&T) -> K + Sync,
+    K: Ord,
+{
+    par_merge_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// O(n^2) work (worst case)\
+/// O(n log n) work (best case)\
+/// O(n log n) work (average)\
+/// O(log n) space per thread\
+/// Not stable, same result as `quick_sort`
+pub fn par_quick_sort<T>(array: &mut [T])
+where
+    T: Ord + Send,
+{
+    par_quick_sort_by(array, T::cmp);
+}
+
+/// O(n^2) work (worst case)\
+/// O(n log n) work (best case)\
+/// O(n log n) work (average)\
+/// O(log n) space per thread\
+/// Not stable, same result as `quick_sort_by`
+pub fn par_quick_sort_by<T, F>(array: &mut [T], compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    par_quick_sort_helper(array, thread_depth(), &compare);
+}
+
+/// O(n^2) work (worst case)\
+/// O(n log n) work (best case)\
+/// O(n log n) work (average)\
+/// O(log n) space per thread\
+/// Not stable, same result as `quick_sort_by_key`
+pub fn par_quick_sort_by_key<T, K, F>(array: &mut [T], key: F)
+where
+    T: Send,
+    F: Fn(&T) -> K + Sync,
+    K: Ord,
+{
+    par_quick_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// Number of times the work may be split in two, so that there
+/// are at least as many tasks as available threads.
+fn thread_depth() -> usize {
+    let threads = thread::available_parallelism().map_or(1, |count| count.get());
+
+    (usize::BITS - (threads - 1).leading_zeros()) as usize
+}
+
+fn par_quick_sort_helper<T, F>(array: &mut [T], depth: usize, compare: &F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let _depth = stats::enter();
+
+    if depth == 0 || array.len() <= SEQUENTIAL_THRESHOLD {
+        quick_sort_helper(array, &mut |a: &T, b: &T| compare(a, b));
+        return;
+    }
+
+    // The very same partitioning steps as the sequential sort
+    let pivot = find_pivot(array);
+    let (less, greater) = find_partition(array, pivot, &mut |a: &T, b: &T| compare(a, b));
+
+    let (left, rest) = array.split_at_mut(less);
+    let right = &mut rest[(greater - less)..];
+
+    thread::scope(|scope| {
+        let child = scope.spawn(|| stats::fork(|| par_quick_sort_helper(left, depth - 1, compare)));
+        par_quick_sort_helper(right, depth - 1, compare);
+        stats::join(child);
+    });
+}
+
+/// `scratch` is uninitialized memory of the same length as `array`.
+fn par_merge_sort_helper<T, F>(
+    array: &mut [T],
+    scratch: &mut [MaybeUninit<T>],
+    depth: usize,
+    compare: &F,
+) where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let _depth = stats::enter();
+    let size = array.len();
+
+    if depth == 0 || size <= SEQUENTIAL_THRESHOLD {
+        merge_sort_by(array, compare);
+        return;
+    }
+
+    let mid = size / 2;
+
+    {
+        let (left, right) = array.split_at_mut(mid);
+        let (left_scratch, right_scratch) = scratch.split_at_mut(mid);
+
+        thread::scope(|scope| {
+            let child = scope.spawn(|| {
+                stats::fork(|| par_merge_sort_helper(left, left_scratch, depth - 1, compare))
+            });
+            par_merge_sort_helper(right, right_scratch, depth - 1, compare);
+            stats::join(child);
+        });
+    }
+
+    // The halves are already in order
+    if compare(&array[mid - 1], &array[mid]) != Ordering::Greater {
+        return;
+    }
+
+    // SAFETY: all values are bitwise moved into `scratch` and merged back into
+    // `array` without dropping whatever `array` holds. Each value is moved out
+    // of `scratch` exactly once. If `compare` panics in any thread, `restore`
+    // copies the untouched `scratch` back over `array`, so `array` owns every
+    // value exactly once again. `scratch` never drops anything.
+    unsafe {
+        ptr::copy_nonoverlapping(array.as_ptr(), scratch.as_mut_ptr() as *mut T, size);
+        stats::moves(size);
+
+        let restore = CopyOnDrop {
+            source: scratch.as_ptr() as *const T,
+            dest: array.as_mut_ptr(),
+            count: size,
+        };
+
+        let dest = &mut *(array as *mut [T] as *mut [MaybeUninit<T>]);
+        let (first, second) = scratch.split_at_mut(mid);
+        par_merge(first, second, dest, depth, compare);
+
+        mem::forget(restore);
+    }
+}
+
+/// Merges the sorted runs `first` and `second` into `dest`, splitting
+/// the work in two independent merges while `depth` allows.
+///
+/// # Safety
+///
+/// `first` and `second` must hold initialized values. They are moved
+/// into `dest` without dropping its contents.
+unsafe fn par_merge<T, F>(
+    first: &mut [MaybeUninit<T>],
+    second: &mut [MaybeUninit<T>],
+    dest: &mut [MaybeUninit<T>],
+    depth: usize,
+    compare: &F,
+) where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if depth == 0 || dest.len() <= SEQUENTIAL_THRESHOLD {
+        merge_into(first, second, dest, compare);
+        return;
+    }
+
+    // Split the longer run in half and the other one around its middle
+    // value, so that everything in the first halves goes first.
+    // Ties always keep values of `first` before those of `second`.
+    let (first_mid, second_mid) = if first.len() >= second.len() {
+        let first_mid = first.len() / 2;
+        let pivot = first[first_mid].assume_init_ref();
+        let second_mid =
+            second.partition_point(|item| compare(item.assume_init_ref(), pivot) == Ordering::Less);
+
+        (first_mid, second_mid)
+    } else {
+        let second_mid = second.len() / 2;
+        let pivot = second[second_mid].assume_init_ref();
+        let first_mid = first
+            .partition_point(|item| compare(item.assume_init_ref(), pivot) != Ordering::Greater);
+
+        (first_mid, second_mid)
+    };
+
+    let (first_left, first_right) = first.split_at_mut(first_mid);
+    let (second_left, second_right) = second.split_at_mut(second_mid);
+    let (dest_left, dest_right) = dest.split_at_mut(first_mid + second_mid);
+
+    thread::scope(|scope| {
+        let child = scope.spawn(|| {
+            stats::fork(|| par_merge(first_left, second_left, dest_left, depth - 1, compare))
+        });
+        par_merge(first_right, second_right, dest_right, depth - 1, compare);
+        stats::join(child);
+    });
+}
+
+/// Sequential part of `par_merge`, with the same safety requirements.
+unsafe fn merge_into<T, F>(
+    first: &[MaybeUninit<T>],
+    second: &[MaybeUninit<T>],
+    dest: &mut [MaybeUninit<T>],
+    compare: &F,
+) where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let mut i = 0;
+    let mut j = 0;
+
+    for slot in dest.iter_mut() {
+        // Ties go to the first run to keep the sort stable
+        let source = if j == second.len()
+            || (i < first.len()
+                && compare(second[j].assume_init_ref(), first[i].assume_init_ref())
+                    != Ordering::Less)
+        {
+            i += 1;
+            &first[i - 1]
+        } else {
+            j += 1;
+            &second[j - 1]
+        };
+
+        slot.write(source.assume_init_read());
+    }
+
+    stats::moves(dest.len());
+}
+
+/// Copies `count` values from `source` to `dest` on drop.
+struct CopyOnDrop<T> {
+    source: *const T,
+    dest: *mut T,
+    count: usize,
+}
+
+impl<T> Drop for CopyOnDrop<T> {
+    fn drop(&mut self) {
+        // SAFETY: both pointers are valid for `count` values and don't overlap
+        unsafe {
+            ptr::copy_nonoverlapping(self.source, self.dest, self.count);
+            stats::moves(self.count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::merge::merge_sort_by_key;
+    use crate::sort::quick::quick_sort_by_key;
+
+    fn pseudo_random(size: usize) -> Vec<(u32, usize)> {
+        let mut state = 0x2545_f491_u32;
+
+        (0..size)
+            .map(|index| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state % 1000, index)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn par_merge_sort_deterministic_test_1() {
+        let mut array = pseudo_random(100_000);
+        let mut expected = array.clone();
+        merge_sort_by_key(&mut expected, |pair| pair.0);
+        par_merge_sort_by_key(&mut array, |pair| pair.0);
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn par_merge_sort_deterministic_test_2() {
+        let mut array = pseudo_random(100_000);
+        let mut expected = array.clone();
+        merge_sort_by_key(&mut expected, |pair| pair.0);
+
+        // Split into 8 tasks regardless of the number of cores
+        let mut buffer = Vec::with_capacity(array.len());
+        let scratch = &mut buffer.spare_capacity_mut()[..array.len()];
+        par_merge_sort_helper(
+            &mut array,
+            scratch,
+            3,
+            &|a: &(u32, usize), b: &(u32, usize)| a.0.cmp(&b.0),
+        );
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn par_quick_sort_deterministic_test_1() {
+        let mut array = pseudo_random(100_000);
+        let mut expected = array.clone();
+        quick_sort_by_key(&mut expected, |pair| pair.0);
+        par_quick_sort_by_key(&mut array, |pair| pair.0);
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn par_quick_sort_deterministic_test_2() {
+        let mut array = pseudo_random(100_000);
+        let mut expected = array.clone();
+        quick_sort_by_key(&mut expected, |pair| pair.0);
+        par_quick_sort_helper(&mut array, 3, &|a: &(u32, usize), b: &(u32, usize)| {
+            a.0.cmp(&b.0)
+        });
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "stats")]
+    fn par_quick_sort_stats_test_1() {
+        use crate::sort::quick::quick_sort_by;
+        use crate::sort::stats::{counting, measure};
+
+        let mut array = pseudo_random(100_000);
+        let mut expected = array.clone();
+        let compare = counting(|a: &(u32, usize), b: &(u32, usize)| a.0.cmp(&b.0));
+
+        // Work done on the spawned threads is counted too
+        let ((), stats) = measure(|| par_quick_sort_helper(&mut array, 3, &compare));
+        let ((), sequential) = measure(|| quick_sort_by(&mut expected, &compare));
+
+        assert_eq!(stats.comparisons, sequential.comparisons);
+        assert_eq!(stats.swaps, sequential.swaps);
+        assert_eq!(stats.max_depth, sequential.max_depth + 3);
+    }
+
+    #[test]
+    fn par_merge_sort_owned_test_1() {
+        let mut array: Vec<String> = pseudo_random(30_000)
+            .into_iter()
+            .map(|pair| pair.0.to_string())
+            .collect();
+        let mut expected = array.clone();
+        expected.sort();
+        par_merge_sort(&mut array);
+
+        assert_eq!(array, expected);
+    }
+}
@@ -0,0 +1,544 @@
+use std::cmp::{min, Ord, Ordering};
+use std::mem;
+
+use crate::sort::heap::heap_sort_by;
+use crate::sort::network::small_sort;
+use crate::sort::sorter::Sorter;
+use crate::sort::stats;
+
+static INSERTION_THRESHOLD: usize = 20;
+static NINTHER_THRESHOLD: usize = 50;
+static MAX_SWAPS: usize = 4 * 3;
+static MAX_INSERTION_STEPS: usize = 5;
+static SHIFTING_THRESHOLD: usize = 50;
+
+const BLOCK: usize = 128;
+
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn pdq_sort<T>(array: &mut [T])
+where
+    T: Ord,
+{
+    pdq_sort_by(array, T::cmp);
+}
+
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn pdq_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // Zero sized values are indistinguishable from each other
+    if mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    let limit = usize::BITS - array.len().leading_zeros();
+    pdq_sort_helper(array, &mut compare, None, limit);
+}
+
+/// O(n log n) time (worst)\
+/// O(n) time (best)\
+/// O(n log n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn pdq_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    pdq_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// `pdq_sort` as a `Sorter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PdqSort;
+
+impl Sorter for PdqSort {
+    const NAME: &'static str = "pdq_sort";
+    const STABLE: bool = false;
+    const IN_PLACE: bool = true;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        pdq_sort_by(array, compare);
+    }
+}
+
+/// `predecessor` is the pivot right before `array` in the whole slice, if any.
+/// `limit` is the number of imbalanced partitions allowed before
+/// switching to heapsort.
+fn pdq_sort_helper<'a, T, F>(
+    mut array: &'a mut [T],
+    compare: &mut F,
+    mut predecessor: Option<&'a T>,
+    mut limit: u32,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let _depth = stats::enter();
+    let mut was_balanced = true;
+    let mut was_partitioned = true;
+
+    loop {
+        let size = array.len();
+
+        if size <= INSERTION_THRESHOLD {
+            small_sort(array, compare);
+            return;
+        }
+
+        if limit == 0 {
+            heap_sort_by(array, &mut *compare);
+            return;
+        }
+
+        if !was_balanced {
+            break_patterns(array);
+            limit -= 1;
+        }
+
+        let (pivot, likely_sorted) = choose_pivot(array, compare);
+
+        // The last partition was decent and did not move anything,
+        // so the array may well be already sorted
+        if was_balanced
+            && was_partitioned
+            && likely_sorted
+            && partial_insertion_sort(array, compare)
+        {
+            return;
+        }
+
+        // The pivot equals the predecessor, which is not greater than anything
+        // in `array`: put all elements equal to it first and skip them
+        if let Some(predecessor) = predecessor {
+            if compare(predecessor, &array[pivot]) != Ordering::Less {
+                let mid = partition_equal(array, pivot, compare);
+                array = &mut array[mid..];
+                continue;
+            }
+        }
+
+        let (mid, already_partitioned) = partition(array, pivot, compare);
+        was_balanced = min(mid, size - mid) >= size / 8;
+        was_partitioned = already_partitioned;
+
+        let (left, right) = array.split_at_mut(mid);
+        let (pivot, right) = right.split_at_mut(1);
+        let pivot = &pivot[0];
+
+        if left.len() < right.len() {
+            pdq_sort_helper(left, compare, predecessor, limit);
+            array = right;
+            predecessor = Some(pivot);
+        } else {
+            pdq_sort_helper(right, compare, Some(pivot), limit);
+            array = left;
+        }
+    }
+}
+
+/// Partitions around `array[pivot]`, which ends up at the returned index.
+/// Also reports whether the array was already partitioned.
+fn partition<T, F>(array: &mut [T], pivot: usize, compare: &mut F) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    array.swap(0, pivot);
+    stats::swap();
+
+    let (head, tail) = array.split_at_mut(1);
+    let pivot = &head[0];
+
+    let mut left = 0;
+    let mut right = tail.len();
+
+    // Skip the elements that are already on the right side
+    while left < right && compare(&tail[left], pivot) == Ordering::Less {
+        left += 1;
+    }
+
+    while left < right && compare(&tail[right - 1], pivot) != Ordering::Less {
+        right -= 1;
+    }
+
+    let mid = left + partition_in_blocks(&mut tail[left..right], pivot, compare);
+    array.swap(0, mid);
+    stats::swap();
+
+    (mid, left >= right)
+}
+
+/// BlockQuicksort partitioning: comparisons only record offsets of misplaced
+/// elements, so the hot loops have no data dependent branches.
+/// Returns the number of elements less than `pivot`, which are moved first.
+fn partition_in_blocks<T, F>(array: &mut [T], pivot: &T, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // Start of the left block and end of the right block
+    let mut left = 0;
+    let mut right = array.len();
+
+    let mut block_left = BLOCK;
+    let mut block_right = BLOCK;
+
+    // Offsets of the elements on the wrong side of each block
+    let mut offsets_left = [0u8; BLOCK];
+    let mut start_left = 0;
+    let mut end_left = 0;
+
+    let mut offsets_right = [0u8; BLOCK];
+    let mut start_right = 0;
+    let mut end_right = 0;
+
+    loop {
+        let is_done = right - left <= 2 * BLOCK;
+
+        // Shrink the blocks so that they cover the remaining gap exactly
+        if is_done {
+            let mut remaining = right - left;
+
+            if start_left < end_left || start_right < end_right {
+                remaining -= BLOCK;
+            }
+
+            if start_left < end_left {
+                block_right = remaining;
+            } else if start_right < end_right {
+                block_left = remaining;
+            } else {
+                block_left = remaining / 2;
+                block_right = remaining - block_left;
+            }
+        }
+
+        if start_left == end_left {
+            start_left = 0;
+            end_left = 0;
+
+            for i in 0..block_left {
+                offsets_left[end_left] = i as u8;
+                end_left += (compare(&array[left + i], pivot) != Ordering::Less) as usize;
+            }
+        }
+
+        if start_right == end_right {
+            start_right = 0;
+            end_right = 0;
+
+            for i in 0..block_right {
+                offsets_right[end_right] = i as u8;
+                end_right += (compare(&array[right - 1 - i], pivot) == Ordering::Less) as usize;
+            }
+        }
+
+        let count = min(end_left - start_left, end_right - start_right);
+
+        for k in 0..count {
+            let first = left + offsets_left[start_left + k] as usize;
+            let second = right - 1 - offsets_right[start_right + k] as usize;
+            array.swap(first, second);
+            stats::swap();
+        }
+
+        start_left += count;
+        start_right += count;
+
+        if start_left == end_left {
+            left += block_left;
+        }
+
+        if start_right == end_right {
+            right -= block_right;
+        }
+
+        if is_done {
+            break;
+        }
+    }
+
+    // At most one block still has misplaced elements, move them to the gap
+    if start_left < end_left {
+        while start_left < end_left {
+            end_left -= 1;
+            right -= 1;
+            array.swap(left + offsets_left[end_left] as usize, right);
+            stats::swap();
+        }
+
+        right
+    } else {
+        while start_right < end_right {
+            end_right -= 1;
+            array.swap(left, right - 1 - offsets_right[end_right] as usize);
+            stats::swap();
+            left += 1;
+        }
+
+        left
+    }
+}
+
+/// Puts the elements equal to `array[pivot]` first, assuming
+/// no element is less than it. Returns their number.
+fn partition_equal<T, F>(array: &mut [T], pivot: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    array.swap(0, pivot);
+    stats::swap();
+
+    let (head, tail) = array.split_at_mut(1);
+    let pivot = &head[0];
+
+    let mut left = 0;
+    let mut right = tail.len();
+
+    loop {
+        while left < right && compare(pivot, &tail[left]) != Ordering::Less {
+            left += 1;
+        }
+
+        while left < right && compare(pivot, &tail[right - 1]) == Ordering::Less {
+            right -= 1;
+        }
+
+        if left >= right {
+            break;
+        }
+
+        right -= 1;
+        tail.swap(left, right);
+        stats::swap();
+        left += 1;
+    }
+
+    left + 1
+}
+
+/// Median of three or Tukey's ninther. The second value tells whether
+/// the samples were already in order. If they were mostly in reverse
+/// order, the array is reversed on the spot.
+fn choose_pivot<T, F>(array: &mut [T], compare: &mut F) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let size = array.len();
+
+    let mut a = size / 4;
+    let mut b = size / 4 * 2;
+    let mut c = size / 4 * 3;
+    let mut swaps = 0;
+
+    if size >= 8 {
+        let mut sample = Sample {
+            array,
+            compare,
+            swaps: &mut swaps,
+        };
+
+        if size >= NINTHER_THRESHOLD {
+            sample.sort_adjacent(&mut a);
+            sample.sort_adjacent(&mut b);
+            sample.sort_adjacent(&mut c);
+        }
+
+        sample.sort3(&mut a, &mut b, &mut c);
+    }
+
+    if swaps < MAX_SWAPS {
+        (b, swaps == 0)
+    } else {
+        array.reverse();
+        stats::swaps(array.len() / 2);
+        (size - 1 - b, true)
+    }
+}
+
+/// Sorts indices into `array` by the values they point to, counting swaps.
+struct Sample<'a, T, F> {
+    array: &'a [T],
+    compare: &'a mut F,
+    swaps: &'a mut usize,
+}
+
+impl<'a, T, F> Sample<'a, T, F>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    fn sort2(&mut self, a: &mut usize, b: &mut usize) {
+        if (self.compare)(&self.array[*b], &self.array[*a]) == Ordering::Less {
+            mem::swap(a, b);
+            *self.swaps += 1;
+        }
+    }
+
+    fn sort3(&mut self, a: &mut usize, b: &mut usize, c: &mut usize) {
+        self.sort2(a, b);
+        self.sort2(b, c);
+        self.sort2(a, b);
+    }
+
+    /// Replaces `a` with the median of `a - 1`, `a` and `a + 1`.
+    fn sort_adjacent(&mut self, a: &mut usize) {
+        let mut before = *a - 1;
+        let mut after = *a + 1;
+        self.sort3(&mut before, a, &mut after);
+    }
+}
+
+/// Swaps a few elements around in a deterministic pseudo random way,
+/// breaking up patterns that lead to unbalanced partitions.
+fn break_patterns<T>(array: &mut [T]) {
+    let size = array.len();
+
+    if size < 8 {
+        return;
+    }
+
+    // Xorshift RNG seeded with the length
+    let mut random = size as u32;
+    let mut next = || {
+        random ^= random << 13;
+        random ^= random >> 17;
+        random ^= random << 5;
+        random as usize
+    };
+
+    let modulus = size.next_power_of_two();
+    let position = size / 4 * 2;
+
+    for i in 0..3 {
+        let mut other = next() & (modulus - 1);
+
+        if other >= size {
+            other -= size;
+        }
+
+        array.swap(position - 1 + i, other);
+        stats::swap();
+    }
+}
+
+/// Fixes up to `MAX_INSERTION_STEPS` adjacent out of order pairs.
+/// Returns `true` if that was enough to sort the array.
+fn partial_insertion_sort<T, F>(array: &mut [T], compare: &mut F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let size = array.len();
+    let mut i = 1;
+
+    for _ in 0..MAX_INSERTION_STEPS {
+        while i < size && compare(&array[i], &array[i - 1]) != Ordering::Less {
+            i += 1;
+        }
+
+        if i == size {
+            return true;
+        }
+
+        // Shifting on short arrays is not worth it
+        if size < SHIFTING_THRESHOLD {
+            return false;
+        }
+
+        array.swap(i - 1, i);
+        stats::swap();
+        shift_tail(&mut array[..i], compare);
+        shift_head(&mut array[i..], compare);
+    }
+
+    false
+}
+
+/// Moves the last element left until it is in order.
+fn shift_tail<T, F>(array: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut i = array.len();
+
+    while i > 1 && compare(&array[i - 1], &array[i - 2]) == Ordering::Less {
+        array.swap(i - 1, i - 2);
+        stats::swap();
+        i -= 1;
+    }
+}
+
+/// Moves the first element right until it is in order.
+fn shift_head<T, F>(array: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut i = 0;
+
+    while i + 1 < array.len() && compare(&array[i + 1], &array[i]) == Ordering::Less {
+        array.swap(i, i + 1);
+        stats::swap();
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pdq_sort_numbers_test_1() {
+        let mut array = [5, 1, 2, 345, 35, 12, 1];
+        pdq_sort(&mut array);
+
+        assert_eq!(array, [1, 1, 2, 5, 12, 35, 345]);
+    }
+
+    #[test]
+    fn pdq_sort_string_test_1() {
+        let mut array = ["aa", "bbc", "bab", "ca", "bac", "bac", "a"];
+        pdq_sort(&mut array);
+
+        assert_eq!(array, ["a", "aa", "bab", "bac", "bac", "bbc", "ca"]);
+    }
+
+    #[test]
+    fn pdq_sort_patterns_test_1() {
+        let patterns: [Vec<i32>; 4] = [
+            (0..2000).map(|i| i % 50).collect(),
+            (0..1000).chain((0..1000).rev()).collect(),
+            (0..2000).map(|i| i * 7919 % 2003).collect(),
+            vec![7; 2000],
+        ];
+
+        for mut array in patterns {
+            let mut expected = array.clone();
+            expected.sort();
+            pdq_sort(&mut array);
+
+            assert_eq!(array, expected);
+        }
+    }
+
+    #[test]
+    fn pdq_sort_presorted_test_1() {
+        let mut comparisons = 0;
+        let mut array: Vec<i32> = (0..1000).collect();
+        pdq_sort_by(&mut array, |a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+
+        assert!(array.iter().copied().eq(0..1000));
+        assert!(comparisons < 2000);
+    }
+}
@@ -1,5 +1,12 @@
 use std::cmp::{Ord, Ordering};
 
+use crate::sort::network::small_sort;
+use crate::sort::sorter::Sorter;
+use crate::sort::stats;
+use crate::sort::trace;
+
+static INSERTION_THRESHOLD: usize = 16;
+
 /// O(n^2) time (worst case)\
 /// O(n log n) time (best case)\
 /// O(n log n) time (average)\
@@ -7,46 +14,118 @@ use std::cmp::{Ord, Ordering};
 /// Not stable
 pub fn quick_sort<T>(array: &mut [T])
 where
-    T: Ord + Copy,
+    T: Ord,
 {
-    quick_sort_helper(array, 0, array.len() - 1);
+    quick_sort_by(array, T::cmp);
 }
 
-fn quick_sort_helper<T>(array: &mut [T], left: usize, right: usize)
+/// O(n^2) time (worst case)\
+/// O(n log n) time (best case)\
+/// O(n log n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn quick_sort_by<T, F>(array: &mut [T], mut compare: F)
 where
-    T: Ord + Copy,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    if left < right {
-        let pivot = find_pivot(array, left, right);
-        let (new_left, new_right) = find_partition(array, pivot, left, right);
-        quick_sort_helper(array, left, new_left.saturating_sub(1));
-        quick_sort_helper(array, new_right, right);
+    quick_sort_helper(array, &mut compare);
+}
+
+/// O(n^2) time (worst case)\
+/// O(n log n) time (best case)\
+/// O(n log n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn quick_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    quick_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+/// `quick_sort` as a `Sorter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuickSort;
+
+impl Sorter for QuickSort {
+    const NAME: &'static str = "quick_sort";
+    const STABLE: bool = false;
+    const IN_PLACE: bool = true;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        quick_sort_by(array, compare);
     }
 }
 
-fn find_pivot<T>(array: &[T], left: usize, right: usize) -> T
+pub(crate) fn quick_sort_helper<T, F>(mut array: &mut [T], compare: &mut F)
 where
-    T: Ord + Copy,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    array[(left + right) / 2]
+    let _depth = stats::enter();
+
+    loop {
+        if array.len() <= INSERTION_THRESHOLD {
+            small_sort(array, compare);
+            return;
+        }
+
+        let pivot = find_pivot(array);
+        let (less, greater) = find_partition(array, pivot, compare);
+        trace::partition(array, less, greater);
+
+        let (left, rest) = array.split_at_mut(less);
+        let right = &mut rest[(greater - less)..];
+
+        // Recurse into the smaller part and loop on the larger one,
+        // so the stack never grows beyond O(log n)
+        if left.len() < right.len() {
+            quick_sort_helper(left, compare);
+            array = right;
+        } else {
+            quick_sort_helper(right, compare);
+            array = left;
+        }
+    }
+}
+
+pub(crate) fn find_pivot<T>(array: &[T]) -> usize {
+    array.len() / 2
 }
 
-fn find_partition<T>(array: &mut [T], pivot: T, mut left: usize, mut right: usize) -> (usize, usize)
+/// Three-way partition around `array[pivot]`.
+/// Returns `(less, greater)`, so that `array[..less]` is less than the pivot,
+/// `array[less..greater]` is equal to it and `array[greater..]` is greater.
+pub(crate) fn find_partition<T, F>(array: &mut [T], pivot: usize, compare: &mut F) -> (usize, usize)
 where
-    T: Ord + Copy,
+    F: FnMut(&T, &T) -> Ordering,
 {
-    let mut upper_bound = right;
-    right = left;
+    array.swap(0, pivot);
+    stats::swap();
+    trace::swap(array, 0, pivot);
 
-    while right <= upper_bound {
-        match pivot.cmp(&array[right]) {
+    let (head, tail) = array.split_at_mut(1);
+    let pivot = &head[0];
+
+    let mut left = 0;
+    let mut right = 0;
+    let mut upper_bound = tail.len();
+
+    while right < upper_bound {
+        match compare(pivot, &tail[right]) {
             Ordering::Less => {
-                array.swap(right, upper_bound);
-                // Can't overflow
                 upper_bound -= 1;
+                tail.swap(right, upper_bound);
+                stats::swap();
+                trace::swap(tail, right, upper_bound);
             }
             Ordering::Greater => {
-                array.swap(left, right);
+                tail.swap(left, right);
+                stats::swap();
+                trace::swap(tail, left, right);
                 left += 1;
                 right += 1;
             }
@@ -54,7 +133,12 @@ where
         }
     }
 
-    (left, right)
+    // Put the pivot between the lesser and the equal elements
+    array.swap(0, left);
+    stats::swap();
+    trace::swap(array, 0, left);
+
+    (left, right + 1)
 }
 
 #[cfg(test)]
@@ -76,4 +160,31 @@ mod tests {
 
         assert_eq!(array, ["a", "aa", "bab", "bac", "bac", "bbc", "ca"]);
     }
+
+    #[test]
+    fn quick_sort_by_floats_test_1() {
+        let mut array = [2.5f64, -1.0, 0.0, 13.25, -7.5];
+        quick_sort_by(&mut array, |a, b| b.total_cmp(a));
+
+        assert_eq!(array, [13.25, 2.5, 0.0, -1.0, -7.5]);
+    }
+
+    #[test]
+    fn quick_sort_by_key_test_1() {
+        let mut array = ["ccc", "a", "bb", "dddd", ""];
+        quick_sort_by_key(&mut array, |string| string.len());
+
+        assert_eq!(array, ["", "a", "bb", "ccc", "dddd"]);
+    }
+
+    #[test]
+    fn quick_sort_owned_test_1() {
+        let mut array: Vec<String> = ["aa", "bbc", "bab", "ca", "bac", "bac", "a"]
+            .iter()
+            .map(|string| string.to_string())
+            .collect();
+        quick_sort(&mut array);
+
+        assert_eq!(array, ["a", "aa", "bab", "bac", "bac", "bbc", "ca"]);
+    }
 }
@@ -0,0 +1,307 @@
+use std::mem;
+
+use crate::sort::stats;
+
+static MSD_INSERTION_THRESHOLD: usize = 16;
+
+/// Primitive integers that can be sorted byte by byte.
+pub trait RadixKey: Copy {
+    /// Number of radix 256 digits in the key.
+    const BYTES: usize;
+
+    /// Returns the `index`-th least significant byte of the key.
+    /// For signed integers the sign bit is flipped, so the unsigned
+    /// order of the bytes matches the numeric order of the keys.
+    fn byte(&self, index: usize) -> u8;
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($unsigned:ty),*) => {
+        $(
+            impl RadixKey for $unsigned {
+                const BYTES: usize = mem::size_of::<$unsigned>();
+
+                fn byte(&self, index: usize) -> u8 {
+                    (*self >> (index * 8)) as u8
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_radix_key_signed {
+    ($($signed:ty => $unsigned:ty),*) => {
+        $(
+            impl RadixKey for $signed {
+                const BYTES: usize = mem::size_of::<$signed>();
+
+                fn byte(&self, index: usize) -> u8 {
+                    let flipped = (*self as $unsigned) ^ (1 << (<$unsigned>::BITS - 1));
+                    (flipped >> (index * 8)) as u8
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+/// Least significant digit first\
+/// O(w * n) time, w = T::BYTES (worst)\
+/// O(w * n) time (best)\
+/// O(w * n) time (average)\
+/// O(n) space\
+/// Stable
+pub fn radix_sort<T>(array: &mut [T])
+where
+    T: RadixKey,
+{
+    radix_sort_by_key(array, |item| *item);
+}
+
+/// Least significant digit first\
+/// O(w * n) time, w = K::BYTES (worst)\
+/// O(w * n) time (best)\
+/// O(w * n) time (average)\
+/// O(n) space\
+/// Stable
+pub fn radix_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    T: Clone,
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    let size = array.len();
+
+    if size < 2 {
+        return;
+    }
+
+    let mut buffer = array.to_vec();
+
+    for digit in 0..K::BYTES {
+        let mut counts = [0; 256];
+
+        for item in array.iter() {
+            counts[key(item).byte(digit) as usize] += 1;
+        }
+
+        // Every key shares this digit, the pass would not move anything
+        if counts.contains(&size) {
+            continue;
+        }
+
+        let mut offset = 0;
+
+        for count in counts.iter_mut() {
+            let current = *count;
+            *count = offset;
+            offset += current;
+        }
+
+        for item in array.iter() {
+            let bucket = key(item).byte(digit) as usize;
+            buffer[counts[bucket]] = item.clone();
+            counts[bucket] += 1;
+        }
+
+        array.clone_from_slice(&buffer);
+        stats::moves(2 * size);
+    }
+}
+
+/// Most significant digit first (American flag sort)\
+/// O(w * n) time, w = average key length (worst)\
+/// O(n) time (best)\
+/// O(w * n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn msd_radix_sort<T>(array: &mut [T])
+where
+    T: AsRef<[u8]>,
+{
+    msd_radix_sort_helper(array, 0, &mut |item: &T, depth| {
+        item.as_ref().get(depth).copied()
+    });
+}
+
+/// Most significant digit first (American flag sort)\
+/// O(w * n) time, w = average key length (worst)\
+/// O(n) time (best)\
+/// O(w * n) time (average)\
+/// O(log n) space\
+/// Not stable
+pub fn msd_radix_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    K: AsRef<[u8]>,
+    F: FnMut(&T) -> K,
+{
+    msd_radix_sort_helper(array, 0, &mut |item: &T, depth| {
+        key(item).as_ref().get(depth).copied()
+    });
+}
+
+/// Bucket 0 holds the keys that end before `depth`.
+fn msd_bucket<T, B>(item: &T, depth: usize, byte: &mut B) -> usize
+where
+    B: FnMut(&T, usize) -> Option<u8>,
+{
+    match byte(item, depth) {
+        Some(value) => value as usize + 1,
+        None => 0,
+    }
+}
+
+fn msd_radix_sort_helper<T, B>(mut array: &mut [T], mut depth: usize, byte: &mut B)
+where
+    B: FnMut(&T, usize) -> Option<u8>,
+{
+    let _depth = stats::enter();
+
+    loop {
+        if array.len() <= MSD_INSERTION_THRESHOLD {
+            msd_insertion_sort(array, depth, byte);
+            return;
+        }
+
+        let mut bounds = [0; 258];
+
+        for item in array.iter() {
+            bounds[msd_bucket(item, depth, byte) + 1] += 1;
+        }
+
+        for bucket in 1..bounds.len() {
+            bounds[bucket] += bounds[bucket - 1];
+        }
+
+        let mut next = bounds;
+
+        for bucket in 0..257 {
+            while next[bucket] < bounds[bucket + 1] {
+                let target = msd_bucket(&array[next[bucket]], depth, byte);
+
+                if target == bucket {
+                    next[bucket] += 1;
+                } else {
+                    array.swap(next[bucket], next[target]);
+                    stats::swap();
+                    next[target] += 1;
+                }
+            }
+        }
+
+        // Recurse into every bucket but the largest one and loop on it,
+        // so the recursion depth stays logarithmic. Bucket 0 is sorted.
+        let largest = (1..257)
+            .max_by_key(|&bucket| bounds[bucket + 1] - bounds[bucket])
+            .unwrap();
+
+        for bucket in (1..257).filter(|&bucket| bucket != largest) {
+            let range = bounds[bucket]..bounds[bucket + 1];
+            msd_radix_sort_helper(&mut array[range], depth + 1, byte);
+        }
+
+        array = &mut array[bounds[largest]..bounds[largest + 1]];
+        depth += 1;
+    }
+}
+
+fn msd_greater<T, B>(a: &T, b: &T, mut depth: usize, byte: &mut B) -> bool
+where
+    B: FnMut(&T, usize) -> Option<u8>,
+{
+    loop {
+        match (byte(a, depth), byte(b, depth)) {
+            (Some(first), Some(second)) if first == second => depth += 1,
+            (Some(first), Some(second)) => return first > second,
+            (first, _) => return first.is_some(),
+        }
+    }
+}
+
+fn msd_insertion_sort<T, B>(array: &mut [T], depth: usize, byte: &mut B)
+where
+    B: FnMut(&T, usize) -> Option<u8>,
+{
+    for i in 1..array.len() {
+        let mut j = i;
+
+        while j > 0 && msd_greater(&array[j - 1], &array[j], depth, byte) {
+            array.swap(j - 1, j);
+            stats::swap();
+            j -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radix_sort_unsigned_test_1() {
+        let mut array = [5u32, 1, 2, 345, 35, 12, 1, u32::MAX, 0, 70000];
+        radix_sort(&mut array);
+
+        assert_eq!(array, [0, 1, 1, 2, 5, 12, 35, 345, 70000, u32::MAX]);
+    }
+
+    #[test]
+    fn radix_sort_signed_test_1() {
+        let mut array = [5i64, -1, 2, i64::MIN, -345, 35, 0, i64::MAX, -12, 1];
+        radix_sort(&mut array);
+
+        assert_eq!(array, [i64::MIN, -345, -12, -1, 0, 1, 2, 5, 35, i64::MAX]);
+    }
+
+    #[test]
+    fn msd_radix_sort_string_test_1() {
+        let mut array = ["aa", "bbc", "bab", "ca", "bac", "bac", "a", "", "ab"];
+        msd_radix_sort(&mut array);
+
+        assert_eq!(
+            array,
+            ["", "a", "aa", "ab", "bab", "bac", "bac", "bbc", "ca"]
+        );
+    }
+
+    #[test]
+    fn msd_radix_sort_string_test_2() {
+        let mut array: Vec<String> = (0..500)
+            .rev()
+            .map(|i| format!("key{}", i * 7 % 500))
+            .collect();
+        let mut expected = array.clone();
+        expected.sort();
+        msd_radix_sort(&mut array);
+
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn radix_sort_by_key_stable_test_1() {
+        let mut array = [
+            (2i8, 'a'),
+            (-1, 'b'),
+            (2, 'c'),
+            (-1, 'd'),
+            (0, 'e'),
+            (2, 'f'),
+        ];
+        radix_sort_by_key(&mut array, |pair| pair.0);
+
+        assert_eq!(
+            array,
+            [(-1, 'b'), (-1, 'd'), (0, 'e'), (2, 'a'), (2, 'c'), (2, 'f')]
+        );
+    }
+
+    #[test]
+    fn msd_radix_sort_by_key_test_1() {
+        let mut array = [(3, "bab"), (1, "aa"), (2, "a"), (0, "ca")];
+        msd_radix_sort_by_key(&mut array, |pair| pair.1);
+
+        assert_eq!(array, [(2, "a"), (1, "aa"), (3, "bab"), (0, "ca")]);
+    }
+}
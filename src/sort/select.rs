@@ -0,0 +1,318 @@
+use std::cmp::{min, Ord, Ordering};
+
+use crate::sort::heap::heapify;
+use crate::sort::network::small_sort;
+use crate::sort::quick::{find_partition, quick_sort_helper};
+use crate::sort::stats;
+
+static GROUP_SIZE: usize = 5;
+
+/// Reorders `array` so that `array[index]` is the value it would hold if
+/// the array was sorted, with no greater value before it and no lesser
+/// value after it.\
+/// O(n^2) time (worst)\
+/// O(n) time (average)\
+/// O(1) space
+pub fn select_nth<T>(array: &mut [T], index: usize) -> &mut T
+where
+    T: Ord,
+{
+    select_nth_by(array, index, T::cmp)
+}
+
+/// Reorders `array` so that `array[index]` is the value it would hold if
+/// the array was sorted, with no greater value before it and no lesser
+/// value after it.\
+/// O(n^2) time (worst)\
+/// O(n) time (average)\
+/// O(1) space
+pub fn select_nth_by<T, F>(array: &mut [T], index: usize, mut compare: F) -> &mut T
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    assert!(index < array.len(), "index out of bounds");
+
+    // Xorshift RNG seeded with the length, a random pivot
+    // makes the running time linear on any input on average
+    let mut random = array.len() as u32 | 1;
+    let mut next = || {
+        random ^= random << 13;
+        random ^= random >> 17;
+        random ^= random << 5;
+        random as usize
+    };
+
+    let mut rest = &mut array[..];
+    let mut rest_index = index;
+
+    loop {
+        let pivot = next() % rest.len();
+        let (less, greater) = find_partition(rest, pivot, &mut compare);
+
+        if rest_index < less {
+            rest = &mut rest[..less];
+        } else if rest_index >= greater {
+            rest = &mut rest[greater..];
+            rest_index -= greater;
+        } else {
+            break;
+        }
+    }
+
+    &mut array[index]
+}
+
+/// Reorders `array` so that `array[index]` is the value it would hold if
+/// the array was sorted, with no greater value before it and no lesser
+/// value after it.\
+/// O(n^2) time (worst)\
+/// O(n) time (average)\
+/// O(1) space
+pub fn select_nth_by_key<T, K, F>(array: &mut [T], index: usize, mut key: F) -> &mut T
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    select_nth_by(array, index, |a, b| key(a).cmp(&key(b)))
+}
+
+/// Same as `select_nth`, with median of medians pivots.\
+/// O(n) time (worst)\
+/// O(log n) space
+pub fn select_nth_deterministic<T>(array: &mut [T], index: usize) -> &mut T
+where
+    T: Ord,
+{
+    select_nth_deterministic_by(array, index, T::cmp)
+}
+
+/// Same as `select_nth_by`, with median of medians pivots.\
+/// O(n) time (worst)\
+/// O(log n) space
+pub fn select_nth_deterministic_by<T, F>(array: &mut [T], index: usize, mut compare: F) -> &mut T
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    assert!(index < array.len(), "index out of bounds");
+
+    median_of_medians_select(array, index, &mut compare);
+
+    &mut array[index]
+}
+
+/// Same as `select_nth_by_key`, with median of medians pivots.\
+/// O(n) time (worst)\
+/// O(log n) space
+pub fn select_nth_deterministic_by_key<T, K, F>(array: &mut [T], index: usize, mut key: F) -> &mut T
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    select_nth_deterministic_by(array, index, |a, b| key(a).cmp(&key(b)))
+}
+
+/// Sorts the `count` least values into `array[..count]`,
+/// the order of the rest is unspecified.\
+/// O(n^2) time (worst)\
+/// O(n + k log k) time (average)\
+/// O(log k) space\
+/// Not stable
+pub fn partial_sort<T>(array: &mut [T], count: usize)
+where
+    T: Ord,
+{
+    partial_sort_by(array, count, T::cmp);
+}
+
+/// Sorts the `count` least values into `array[..count]`,
+/// the order of the rest is unspecified.\
+/// O(n^2) time (worst)\
+/// O(n + k log k) time (average)\
+/// O(log k) space\
+/// Not stable
+pub fn partial_sort_by<T, F>(array: &mut [T], count: usize, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let count = min(count, array.len());
+
+    if count == 0 {
+        return;
+    }
+
+    select_nth_by(array, count - 1, &mut compare);
+    quick_sort_helper(&mut array[..(count - 1)], &mut compare);
+}
+
+/// Sorts the `count` least values into `array[..count]`,
+/// the order of the rest is unspecified.\
+/// O(n^2) time (worst)\
+/// O(n + k log k) time (average)\
+/// O(log k) space\
+/// Not stable
+pub fn partial_sort_by_key<T, K, F>(array: &mut [T], count: usize, mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    partial_sort_by(array, count, |a, b| key(a).cmp(&key(b)));
+}
+
+/// Moves the `count` greatest values to the front in descending
+/// order and returns them, the order of the rest is unspecified.\
+/// O(n log k) time\
+/// O(1) space\
+/// Not stable
+pub fn top_k<T>(array: &mut [T], count: usize) -> &mut [T]
+where
+    T: Ord,
+{
+    top_k_by(array, count, T::cmp)
+}
+
+/// Moves the `count` greatest values to the front in descending
+/// order and returns them, the order of the rest is unspecified.\
+/// O(n log k) time\
+/// O(1) space\
+/// Not stable
+pub fn top_k_by<T, F>(array: &mut [T], count: usize, mut compare: F) -> &mut [T]
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let count = min(count, array.len());
+
+    // `heapify` builds max heaps, so flip the order to keep
+    // the least of the current top values on top
+    let mut reversed = |a: &T, b: &T| compare(b, a);
+
+    for i in (0..count / 2).rev() {
+        heapify(array, count, i, &mut reversed);
+    }
+
+    for i in count..array.len() {
+        if count > 0 && reversed(&array[i], &array[0]) == Ordering::Less {
+            array.swap(0, i);
+            stats::swap();
+            heapify(array, count, 0, &mut reversed);
+        }
+    }
+
+    // Popping the least value to the back each time leaves them in descending order
+    for i in (1..count).rev() {
+        array.swap(0, i);
+        stats::swap();
+        heapify(array, i, 0, &mut reversed);
+    }
+
+    &mut array[..count]
+}
+
+/// Moves the `count` greatest values to the front in descending
+/// order and returns them, the order of the rest is unspecified.\
+/// O(n log k) time\
+/// O(1) space\
+/// Not stable
+pub fn top_k_by_key<T, K, F>(array: &mut [T], count: usize, mut key: F) -> &mut [T]
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    top_k_by(array, count, |a, b| key(a).cmp(&key(b)))
+}
+
+fn median_of_medians_select<T, F>(mut array: &mut [T], mut index: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let _depth = stats::enter();
+
+    loop {
+        let size = array.len();
+
+        if size <= GROUP_SIZE {
+            small_sort(array, compare);
+            return;
+        }
+
+        // Move the median of every group of five to the front
+        let groups = (size + GROUP_SIZE - 1) / GROUP_SIZE;
+
+        for group in 0..groups {
+            let start = group * GROUP_SIZE;
+            let end = min(start + GROUP_SIZE, size);
+
+            small_sort(&mut array[start..end], compare);
+            array.swap(group, start + (end - start) / 2);
+            stats::swap();
+        }
+
+        let pivot = groups / 2;
+        median_of_medians_select(&mut array[..groups], pivot, compare);
+
+        let (less, greater) = find_partition(array, pivot, compare);
+
+        if index < less {
+            array = &mut array[..less];
+        } else if index >= greater {
+            array = &mut array[greater..];
+            index -= greater;
+        } else {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random(size: usize) -> Vec<u32> {
+        (0..size as u32).map(|i| i * 7919 % 1009).collect()
+    }
+
+    #[test]
+    fn select_nth_test_1() {
+        let array = pseudo_random(1000);
+        let mut expected = array.clone();
+        expected.sort();
+
+        for index in [0, 1, 499, 500, 998, 999] {
+            let mut randomized = array.clone();
+            let mut deterministic = array.clone();
+
+            assert_eq!(*select_nth(&mut randomized, index), expected[index]);
+            assert_eq!(
+                *select_nth_deterministic(&mut deterministic, index),
+                expected[index]
+            );
+
+            for values in [randomized, deterministic] {
+                assert!(values[..index]
+                    .iter()
+                    .all(|&value| value <= expected[index]));
+                assert!(values[index..]
+                    .iter()
+                    .all(|&value| value >= expected[index]));
+            }
+        }
+    }
+
+    #[test]
+    fn partial_sort_test_1() {
+        let mut array = pseudo_random(1000);
+        let mut expected = array.clone();
+        expected.sort();
+        partial_sort(&mut array, 10);
+
+        assert_eq!(array[..10], expected[..10]);
+    }
+
+    #[test]
+    fn top_k_test_1() {
+        let mut array = ["aa", "bbc", "bab", "ca", "bac", "bac", "a"];
+
+        assert_eq!(top_k(&mut array, 3), ["ca", "bbc", "bac"]);
+        assert_eq!(top_k_by_key(&mut array, 2, |string| string.len()).len(), 2);
+        assert!(top_k(&mut array, 0).is_empty());
+    }
+}
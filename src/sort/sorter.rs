@@ -0,0 +1,182 @@
+use std::cmp::{Ord, Ordering};
+
+/// Comparison sort over any slice, implemented by a unit struct per algorithm.
+///
+/// Sorts with extra bounds on the values (`radix`, `counting`, `bucket`),
+/// on the comparator (`parallel`) or on the length (`network_sort`)
+/// don't implement it.
+pub trait Sorter {
+    /// Name of the matching free function.
+    const NAME: &'static str;
+
+    /// Equal values keep their relative order.
+    const STABLE: bool;
+
+    /// Needs at most O(log n) extra space.
+    const IN_PLACE: bool;
+
+    fn sort_by<T, F>(&self, array: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    fn sort<T>(&self, array: &mut [T])
+    where
+        T: Ord,
+    {
+        self.sort_by(array, T::cmp);
+    }
+
+    fn sort_by_key<T, K, F>(&self, array: &mut [T], mut key: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.sort_by(array, |a, b| key(a).cmp(&key(b)));
+    }
+}
+
+/// Declares a variant of `Algorithm` for every `Sorter`,
+/// each one only present with its cargo feature enabled.
+macro_rules! registry {
+    ($($feature:literal => $variant:ident($sorter:path)),* $(,)?) => {
+        /// Sorters enabled by the current feature set, to pick one at runtime.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Algorithm {
+            $(
+                #[cfg(feature = $feature)]
+                $variant,
+            )*
+        }
+
+        impl Algorithm {
+            /// Every enabled algorithm.
+            pub fn all() -> &'static [Algorithm] {
+                &[
+                    $(
+                        #[cfg(feature = $feature)]
+                        Algorithm::$variant,
+                    )*
+                ]
+            }
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Algorithm::$variant => <$sorter as Sorter>::NAME,
+                    )*
+                }
+            }
+
+            pub fn is_stable(self) -> bool {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Algorithm::$variant => <$sorter as Sorter>::STABLE,
+                    )*
+                }
+            }
+
+            pub fn is_in_place(self) -> bool {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Algorithm::$variant => <$sorter as Sorter>::IN_PLACE,
+                    )*
+                }
+            }
+
+            // Without any sorter enabled the match has no arms
+            #[allow(unused_variables)]
+            pub fn sort_by<T, F>(self, array: &mut [T], compare: F)
+            where
+                F: FnMut(&T, &T) -> Ordering,
+            {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Algorithm::$variant => $sorter.sort_by(array, compare),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+registry! {
+    "bubble" => Bubble(crate::sort::bubble::BubbleSort),
+    "network" => Insertion(crate::sort::network::InsertionSort),
+    "network" => BinaryInsertion(crate::sort::network::BinaryInsertionSort),
+    "quick" => Quick(crate::sort::quick::QuickSort),
+    "merge" => Merge(crate::sort::merge::MergeSort),
+    "merge" => Tim(crate::sort::merge::TimSort),
+    "heap" => Heap(crate::sort::heap::HeapSort),
+    "intro" => Intro(crate::sort::intro::IntroSort),
+    "pdq" => Pdq(crate::sort::pdq::PdqSort),
+}
+
+impl Algorithm {
+    /// Looks an enabled algorithm up by the name of its free function.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|algorithm| algorithm.name() == name)
+    }
+
+    pub fn sort<T>(self, array: &mut [T])
+    where
+        T: Ord,
+    {
+        self.sort_by(array, T::cmp);
+    }
+
+    pub fn sort_by_key<T, K, F>(self, array: &mut [T], mut key: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.sort_by(array, |a, b| key(a).cmp(&key(b)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn algorithm_all_test_1() {
+        let expected = ["a", "aa", "bab", "bac", "bac", "bbc", "ca"];
+
+        for &algorithm in Algorithm::all() {
+            let mut array = ["aa", "bbc", "bab", "ca", "bac", "bac", "a"];
+            algorithm.sort(&mut array);
+
+            assert_eq!(array, expected, "{}", algorithm.name());
+            assert_eq!(Algorithm::from_name(algorithm.name()), Some(algorithm));
+        }
+
+        assert_eq!(Algorithm::from_name("bogo_sort"), None);
+    }
+
+    #[test]
+    fn algorithm_stable_test_1() {
+        let array: Vec<(usize, usize)> = (0..500).map(|i| (i * 7919 % 13, i)).collect();
+        let mut expected = array.clone();
+        expected.sort_by_key(|pair| pair.0);
+
+        for &algorithm in Algorithm::all() {
+            let mut sorted = array.clone();
+            algorithm.sort_by_key(&mut sorted, |pair| pair.0);
+
+            assert!(sorted
+                .iter()
+                .map(|pair| pair.0)
+                .eq(expected.iter().map(|pair| pair.0)));
+
+            if algorithm.is_stable() {
+                assert_eq!(sorted, expected, "{}", algorithm.name());
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// Instrumentation of the sorts. The sorts report their swaps, moves and
+// recursion through the hooks below, which do nothing unless the `stats`
+// feature is enabled. Comparisons are counted by wrapping the comparator.
+
+#[cfg(feature = "stats")]
+use std::cell::Cell;
+#[cfg(feature = "stats")]
+use std::cmp::Ordering;
+use std::panic;
+use std::thread::ScopedJoinHandle;
+
+/// What a sort did on the measured thread and the threads it spawned.
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortStats {
+    /// Calls to comparators wrapped with `counting`.
+    pub comparisons: usize,
+    /// Exchanges of two values.
+    pub swaps: usize,
+    /// Values moved on their own, e.g. into or out of a scratch buffer.
+    pub moves: usize,
+    /// Deepest nesting of recursive calls, 0 for iterative sorts.
+    pub max_depth: usize,
+}
+
+#[cfg(feature = "stats")]
+thread_local! {
+    static STATS: Cell<SortStats> = Cell::new(SortStats::default());
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Runs `sort` and returns what it did.
+/// Measurements can be nested, the outer one includes the inner ones.
+#[cfg(feature = "stats")]
+pub fn measure<R, S>(sort: S) -> (R, SortStats)
+where
+    S: FnOnce() -> R,
+{
+    let scope = Scope {
+        outer: STATS.with(Cell::take),
+        depth: DEPTH.with(|depth| depth.replace(0)),
+    };
+
+    let result = sort();
+    let stats = STATS.with(Cell::get);
+    drop(scope);
+
+    (result, stats)
+}
+
+/// Wraps `compare` so that `measure` counts every call.
+#[cfg(feature = "stats")]
+pub fn counting<T, F>(compare: F) -> impl Fn(&T, &T) -> Ordering + Sync
+where
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    move |a, b| {
+        update(|stats| stats.comparisons += 1);
+        compare(a, b)
+    }
+}
+
+/// Restores the outer measurement and adds the inner one to it, even on panic.
+#[cfg(feature = "stats")]
+struct Scope {
+    outer: SortStats,
+    depth: usize,
+}
+
+#[cfg(feature = "stats")]
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let inner = STATS.with(|stats| stats.replace(self.outer));
+        DEPTH.with(|depth| depth.set(self.depth));
+        absorb(inner);
+    }
+}
+
+#[cfg(feature = "stats")]
+fn update<U>(update: U)
+where
+    U: FnOnce(&mut SortStats),
+{
+    STATS.with(|cell| {
+        let mut stats = cell.get();
+        update(&mut stats);
+        cell.set(stats);
+    });
+}
+
+/// Adds what another thread did, from the current depth on.
+#[cfg(feature = "stats")]
+fn absorb(child: SortStats) {
+    let depth = DEPTH.with(Cell::get);
+
+    update(|stats| {
+        stats.comparisons += child.comparisons;
+        stats.swaps += child.swaps;
+        stats.moves += child.moves;
+        stats.max_depth = stats.max_depth.max(depth + child.max_depth);
+    });
+}
+
+#[inline]
+pub(crate) fn swap() {
+    #[cfg(feature = "stats")]
+    update(|stats| stats.swaps += 1);
+}
+
+#[inline]
+pub(crate) fn swaps(count: usize) {
+    #[cfg(feature = "stats")]
+    update(|stats| stats.swaps += count);
+
+    #[cfg(not(feature = "stats"))]
+    let _ = count;
+}
+
+#[inline]
+pub(crate) fn moves(count: usize) {
+    #[cfg(feature = "stats")]
+    update(|stats| stats.moves += count);
+
+    #[cfg(not(feature = "stats"))]
+    let _ = count;
+}
+
+/// Marks a recursive call until the returned guard is dropped.
+#[inline]
+pub(crate) fn enter() -> Depth {
+    #[cfg(feature = "stats")]
+    {
+        let depth = DEPTH.with(|depth| {
+            depth.set(depth.get() + 1);
+            depth.get()
+        });
+
+        update(|stats| stats.max_depth = stats.max_depth.max(depth));
+    }
+
+    Depth
+}
+
+#[must_use]
+pub(crate) struct Depth;
+
+#[cfg(feature = "stats")]
+impl Drop for Depth {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// What a spawned thread did, to hand back to its parent with `join`.
+pub(crate) struct Forked {
+    #[cfg(feature = "stats")]
+    stats: SortStats,
+}
+
+/// Runs the body of a spawned thread.
+pub(crate) fn fork<W>(work: W) -> Forked
+where
+    W: FnOnce(),
+{
+    #[cfg(feature = "stats")]
+    {
+        let ((), stats) = measure(work);
+        Forked { stats }
+    }
+
+    #[cfg(not(feature = "stats"))]
+    {
+        work();
+        Forked {}
+    }
+}
+
+/// Waits for a thread started with `fork` and adds what it did.
+pub(crate) fn join(handle: ScopedJoinHandle<'_, Forked>) {
+    match handle.join() {
+        #[cfg(feature = "stats")]
+        Ok(forked) => absorb(forked.stats),
+        #[cfg(not(feature = "stats"))]
+        Ok(_) => (),
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "stats",
+    feature = "bubble",
+    feature = "heap",
+    feature = "merge",
+    feature = "quick",
+))]
+mod tests {
+    use super::*;
+    use crate::sort::bubble::bubble_sort_by;
+    use crate::sort::heap::heap_sort_by;
+    use crate::sort::merge::merge_sort_by;
+    use crate::sort::quick::quick_sort_by;
+
+    #[test]
+    fn bubble_sort_stats_test_1() {
+        let size = 100;
+        let mut array: Vec<usize> = (0..size).rev().collect();
+        let ((), stats) = measure(|| bubble_sort_by(&mut array, counting(usize::cmp)));
+
+        assert_eq!(stats.comparisons, size * (size - 1) / 2);
+        assert_eq!(stats.swaps, size * (size - 1) / 2);
+        assert_eq!(stats.max_depth, 0);
+
+        let ((), stats) = measure(|| bubble_sort_by(&mut array, counting(usize::cmp)));
+
+        assert_eq!(stats.comparisons, size - 1);
+        assert_eq!(stats.swaps, 0);
+    }
+
+    #[test]
+    fn n_log_n_sort_stats_test_1() {
+        let size = 1 << 12;
+        let log = 12;
+        let array: Vec<usize> = (0..size).map(|i| i * 7919 % size).collect();
+
+        let mut merged = array.clone();
+        let ((), stats) = measure(|| merge_sort_by(&mut merged, counting(usize::cmp)));
+
+        // At most n - 1 per level of merges and 16 * 15 / 2 per insertion sorted chunk
+        assert!(stats.comparisons <= size * (log - 4) + size / 16 * 120);
+        assert!(stats.moves <= 2 * size * log);
+        assert!(stats.max_depth <= log);
+
+        let mut heaped = array.clone();
+        let ((), stats) = measure(|| heap_sort_by(&mut heaped, counting(usize::cmp)));
+
+        assert!(stats.comparisons <= 2 * size * log);
+        assert!(stats.swaps <= size * log);
+
+        let mut quick = array;
+        let ((), stats) = measure(|| quick_sort_by(&mut quick, counting(usize::cmp)));
+
+        // Recursing into the smaller part only
+        assert!(stats.max_depth <= log);
+        assert!(stats.comparisons < size * log * 2);
+    }
+
+    #[test]
+    fn nested_stats_test_1() {
+        let mut array = [3, 2, 1];
+
+        let (inner, outer) = measure(|| {
+            let ((), inner) = measure(|| bubble_sort_by(&mut array, counting(i32::cmp)));
+            inner
+        });
+
+        assert_eq!(inner.swaps, 3);
+        assert_eq!(outer, inner);
+    }
+}
@@ -0,0 +1,266 @@
+// Differential tests of every sort against `slice::sort`, on generated
+// inputs of the shapes that tend to break sorts: empty, single values,
+// many duplicates, already sorted and reversed.
+
+use std::cmp::Ordering;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::Arc;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::sort::sorter::Algorithm;
+
+type Sorts<T> = Vec<(&'static str, fn(&mut [T]))>;
+type PanickingSorts = Vec<(&'static str, fn(&mut [Arc<i32>], &PanickingCompare))>;
+
+/// Slice sorts of `i32` that don't implement `Sorter`.
+fn other_sorts() -> Sorts<i32> {
+    #[allow(unused_mut)]
+    let mut sorts: Sorts<i32> = Vec::new();
+
+    #[cfg(feature = "radix")]
+    {
+        sorts.push(("radix_sort", crate::sort::radix::radix_sort));
+        sorts.push(("msd_radix_sort", |array| {
+            crate::sort::radix::msd_radix_sort_by_key(array, |&item| {
+                ((item as u32) ^ (1 << 31)).to_be_bytes()
+            })
+        }));
+    }
+
+    // Keys are 32 bits wide, so sort by the low half and then
+    // by the high half, which only works if the sort is stable
+    #[cfg(feature = "counting")]
+    sorts.push(("counting_sort", |array| {
+        let max_key = u16::MAX as usize;
+        let flipped = |item: &i32| (*item as u32) ^ (1 << 31);
+
+        crate::sort::counting::counting_sort_by_key(array, max_key, |item| {
+            flipped(item) as usize & max_key
+        });
+        crate::sort::counting::counting_sort_by_key(array, max_key, |item| {
+            (flipped(item) >> 16) as usize
+        });
+    }));
+
+    #[cfg(feature = "bucket")]
+    sorts.push(("bucket_sort", |array| {
+        let mut floats: Vec<f64> = array.iter().map(|&item| item as f64).collect();
+        crate::sort::bucket::bucket_sort(&mut floats);
+
+        for (item, float) in array.iter_mut().zip(floats) {
+            *item = float as i32;
+        }
+    }));
+
+    #[cfg(feature = "select")]
+    sorts.push(("partial_sort", |array| {
+        crate::sort::select::partial_sort(array, array.len())
+    }));
+
+    #[cfg(feature = "parallel")]
+    {
+        sorts.push(("par_merge_sort", crate::sort::parallel::par_merge_sort));
+        sorts.push(("par_quick_sort", crate::sort::parallel::par_quick_sort));
+    }
+
+    sorts
+}
+
+/// Stable sorts by key of `(key, index)` pairs that don't implement `Sorter`.
+fn other_stable_sorts() -> Sorts<(u8, usize)> {
+    #[allow(unused_mut)]
+    let mut sorts: Sorts<(u8, usize)> = Vec::new();
+
+    #[cfg(feature = "radix")]
+    sorts.push(("radix_sort", |array| {
+        crate::sort::radix::radix_sort_by_key(array, |pair| pair.0)
+    }));
+
+    #[cfg(feature = "counting")]
+    sorts.push(("counting_sort", |array| {
+        crate::sort::counting::counting_sort_by_key(array, u8::MAX as usize, |pair| pair.0 as usize)
+    }));
+
+    #[cfg(feature = "parallel")]
+    sorts.push(("par_merge_sort", |array| {
+        crate::sort::parallel::par_merge_sort_by_key(array, |pair| pair.0)
+    }));
+
+    sorts
+}
+
+/// Comparison sorts that don't implement `Sorter`, with a shared comparator.
+fn other_panicking_sorts() -> PanickingSorts {
+    #[allow(unused_mut)]
+    let mut sorts: PanickingSorts = Vec::new();
+
+    #[cfg(feature = "select")]
+    sorts.push(("partial_sort", |array, compare| {
+        let count = array.len();
+        crate::sort::select::partial_sort_by(array, count, |a, b| compare.compare(a, b))
+    }));
+
+    #[cfg(feature = "parallel")]
+    {
+        sorts.push(("par_merge_sort", |array, compare| {
+            crate::sort::parallel::par_merge_sort_by(array, |a, b| compare.compare(a, b))
+        }));
+        sorts.push(("par_quick_sort", |array, compare| {
+            crate::sort::parallel::par_quick_sort_by(array, |a, b| compare.compare(a, b))
+        }));
+    }
+
+    sorts
+}
+
+/// Compares by value and panics on the `limit`-th call.
+struct PanickingCompare {
+    calls: AtomicUsize,
+    limit: usize,
+}
+
+impl PanickingCompare {
+    fn new(limit: usize) -> Self {
+        PanickingCompare {
+            calls: AtomicUsize::new(0),
+            limit,
+        }
+    }
+
+    fn compare(&self, a: &Arc<i32>, b: &Arc<i32>) -> Ordering {
+        if self.calls.fetch_add(1, atomic::Ordering::Relaxed) + 1 == self.limit {
+            panic!("comparison {}", self.limit);
+        }
+
+        a.cmp(b)
+    }
+}
+
+/// Sorts a copy of `values` with a comparator that panics on the `limit`-th
+/// call, then checks that every value is still there exactly once.
+fn check_panic_safety<S>(
+    name: &str,
+    values: &[Arc<i32>],
+    limit: usize,
+    sort: S,
+) -> Result<(), TestCaseError>
+where
+    S: FnOnce(&mut [Arc<i32>], &PanickingCompare),
+{
+    let mut expected: Vec<i32> = values.iter().map(|value| **value).collect();
+    expected.sort();
+
+    let mut sorted = values.to_vec();
+    let compare = PanickingCompare::new(limit);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| sort(&mut sorted, &compare)));
+
+    // Neither dropped nor duplicated, whether or not the comparator panicked
+    let mut remaining: Vec<i32> = sorted.iter().map(|value| **value).collect();
+    remaining.sort();
+    prop_assert_eq!(&remaining, &expected, "{}", name);
+    prop_assert!(
+        values.iter().all(|value| Arc::strong_count(value) == 2),
+        "{}",
+        name
+    );
+
+    if result.is_ok() {
+        prop_assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]), "{}", name);
+    }
+
+    Ok(())
+}
+
+/// Empty, single value, duplicate heavy, sorted, reversed and random arrays.
+fn arrays(max_size: usize) -> impl Strategy<Value = Vec<i32>> {
+    prop_oneof![
+        vec(any::<i32>(), 0..2),
+        vec(0..4, 0..max_size),
+        vec(any::<i32>(), 0..max_size).prop_map(|mut array| {
+            array.sort();
+            array
+        }),
+        vec(any::<i32>(), 0..max_size).prop_map(|mut array| {
+            array.sort_by(|a, b| b.cmp(a));
+            array
+        }),
+        vec(any::<i32>(), 0..max_size),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn sort_differential_test_1(array in arrays(300)) {
+        let mut expected = array.clone();
+        expected.sort();
+
+        for &algorithm in Algorithm::all() {
+            let mut sorted = array.clone();
+            algorithm.sort(&mut sorted);
+            prop_assert_eq!(&sorted, &expected, "{}", algorithm.name());
+        }
+
+        for (name, sort) in other_sorts() {
+            let mut sorted = array.clone();
+            sort(&mut sorted);
+            prop_assert_eq!(&sorted, &expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn sort_stable_test_1(keys in vec(0..8u8, 0..300)) {
+        let array: Vec<(u8, usize)> = keys.into_iter().enumerate().map(|(i, key)| (key, i)).collect();
+        let mut expected = array.clone();
+        expected.sort_by_key(|pair| pair.0);
+
+        for &algorithm in Algorithm::all().iter().filter(|algorithm| algorithm.is_stable()) {
+            let mut sorted = array.clone();
+            algorithm.sort_by_key(&mut sorted, |pair| pair.0);
+            prop_assert_eq!(&sorted, &expected, "{}", algorithm.name());
+        }
+
+        for (name, sort) in other_stable_sorts() {
+            let mut sorted = array.clone();
+            sort(&mut sorted);
+            prop_assert_eq!(&sorted, &expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn sort_panic_safety_test_1(array in arrays(300), limit in 1..2000usize) {
+        let values: Vec<Arc<i32>> = array.into_iter().map(Arc::new).collect();
+
+        for &algorithm in Algorithm::all() {
+            check_panic_safety(algorithm.name(), &values, limit, |array, compare| {
+                algorithm.sort_by(array, |a, b| compare.compare(a, b))
+            })?;
+        }
+
+        for (name, sort) in other_panicking_sorts() {
+            check_panic_safety(name, &values, limit, sort)?;
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    // Long enough for the parallel sorts to split across threads
+    #[test]
+    fn par_sort_differential_test_1(array in arrays(1 << 15)) {
+        let mut expected = array.clone();
+        expected.sort();
+
+        let mut merged = array.clone();
+        crate::sort::parallel::par_merge_sort(&mut merged);
+        prop_assert_eq!(&merged, &expected);
+
+        let mut quick = array;
+        crate::sort::parallel::par_quick_sort(&mut quick);
+        prop_assert_eq!(&quick, &expected);
+    }
+}
@@ -0,0 +1,500 @@
+// Event stream of a sort, for animations. The sorts report what they do
+// through the hooks below, which do nothing unless the `trace` feature is
+// enabled. Positions are recovered from the addresses of the values, so the
+// hooks need no extra arguments threaded through the recursion.
+
+#[cfg(feature = "trace")]
+use std::cell::Cell;
+#[cfg(feature = "trace")]
+use std::cmp::{Ord, Ordering};
+#[cfg(feature = "trace")]
+use std::fs::File;
+#[cfg(feature = "trace")]
+use std::io::{self, BufWriter, Write};
+use std::mem;
+#[cfg(feature = "trace")]
+use std::path::Path;
+
+#[cfg(feature = "trace")]
+use crate::sort::sorter::Sorter;
+
+/// Position of a value while a sort runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slot {
+    /// Index into the array being sorted.
+    Array(usize),
+    /// Index into the scratch buffer of `merge_sort`.
+    Buffer(usize),
+    /// Anywhere else, e.g. a temporary copy or a buffer
+    /// of a sort that reports only its comparisons.
+    Other,
+}
+
+/// Step of a sort. Replaying the swaps and writes on a copy of the
+/// input, with a scratch buffer next to it, gives the sorted array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// The values in two slots were compared.
+    Compare(Slot, Slot),
+    /// `array[i]` and `array[j]` were exchanged.
+    Swap(usize, usize),
+    /// The value in `from` was copied into `to`.
+    Write { to: Slot, from: Slot },
+    /// `array[start..end]` was partitioned around a pivot: `array[start..less]`
+    /// is less than it, `array[less..greater]` equal and `array[greater..end]` greater.
+    Partition {
+        start: usize,
+        less: usize,
+        greater: usize,
+        end: usize,
+    },
+    /// Sifting down from `root` in the heap `array[start..start + size]`.
+    Heapify {
+        start: usize,
+        root: usize,
+        size: usize,
+    },
+}
+
+/// Sorts `array` with `sorter` and calls `observer` for every step.
+/// `quick_sort`, `merge_sort`, `heap_sort` and `bubble_sort` report all of
+/// their steps, the other sorts only their comparisons.
+#[cfg(feature = "trace")]
+pub fn trace<T, S, O>(sorter: S, array: &mut [T], observer: O)
+where
+    T: Ord,
+    S: Sorter,
+    O: FnMut(Event),
+{
+    trace_by(sorter, array, T::cmp, observer);
+}
+
+/// Sorts `array` with `sorter` and calls `observer` for every step.
+/// `quick_sort`, `merge_sort`, `heap_sort` and `bubble_sort` report all of
+/// their steps, the other sorts only their comparisons.
+#[cfg(feature = "trace")]
+pub fn trace_by<T, S, F, O>(sorter: S, array: &mut [T], mut compare: F, mut observer: O)
+where
+    S: Sorter,
+    F: FnMut(&T, &T) -> Ordering,
+    O: FnMut(Event),
+{
+    // Zero sized values have no positions to tell apart
+    if mem::size_of::<T>() == 0 {
+        sorter.sort_by(array, compare);
+        return;
+    }
+
+    let observer: &mut dyn FnMut(Event) = &mut observer;
+    let start = array.as_ptr() as usize;
+
+    // SAFETY: only the lifetime is erased. `Scope` takes the tracer back out
+    // of the thread local before `observer` goes out of scope, even on panic.
+    let tracer = Tracer {
+        observer: unsafe { mem::transmute(observer) },
+        start,
+        end: start + mem::size_of_val(array),
+        size: mem::size_of::<T>(),
+        buffer_start: 0,
+        buffer_end: 0,
+    };
+
+    let scope = Scope {
+        previous: TRACER.with(|cell| cell.replace(Some(tracer))),
+    };
+
+    sorter.sort_by(array, |a, b| {
+        emit(mem::size_of::<T>(), |tracer| {
+            Some(Event::Compare(tracer.slot(a), tracer.slot(b)))
+        });
+        compare(a, b)
+    });
+
+    drop(scope);
+}
+
+/// Writes events as JSON lines, e.g. `{"event":"swap","i":0,"j":3}`.
+#[cfg(feature = "trace")]
+#[derive(Debug)]
+pub struct Recorder<W>
+where
+    W: Write,
+{
+    output: W,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "trace")]
+impl Recorder<BufWriter<File>> {
+    pub fn create<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+#[cfg(feature = "trace")]
+impl<W> Recorder<W>
+where
+    W: Write,
+{
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            error: None,
+        }
+    }
+
+    /// Nothing is written after the first error, `finish` returns it.
+    pub fn record(&mut self, event: Event) {
+        if self.error.is_none() {
+            if let Err(error) = writeln!(self.output, "{}", json(event)) {
+                self.error = Some(error);
+            }
+        }
+    }
+
+    /// Flushes and returns the output.
+    pub fn finish(mut self) -> io::Result<W> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.output.flush().map(|()| self.output),
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+fn json(event: Event) -> String {
+    let slot = |slot| match slot {
+        Slot::Array(index) => format!("{{\"array\":{}}}", index),
+        Slot::Buffer(index) => format!("{{\"buffer\":{}}}", index),
+        Slot::Other => "\"other\"".to_string(),
+    };
+
+    match event {
+        Event::Compare(a, b) => format!(
+            "{{\"event\":\"compare\",\"a\":{},\"b\":{}}}",
+            slot(a),
+            slot(b)
+        ),
+        Event::Swap(i, j) => format!("{{\"event\":\"swap\",\"i\":{},\"j\":{}}}", i, j),
+        Event::Write { to, from } => format!(
+            "{{\"event\":\"write\",\"to\":{},\"from\":{}}}",
+            slot(to),
+            slot(from)
+        ),
+        Event::Partition {
+            start,
+            less,
+            greater,
+            end,
+        } => format!(
+            "{{\"event\":\"partition\",\"start\":{},\"less\":{},\"greater\":{},\"end\":{}}}",
+            start, less, greater, end
+        ),
+        Event::Heapify { start, root, size } => format!(
+            "{{\"event\":\"heapify\",\"start\":{},\"root\":{},\"size\":{}}}",
+            start, root, size
+        ),
+    }
+}
+
+/// The traced array by address, and where to send its events.
+#[derive(Clone, Copy)]
+struct Tracer {
+    observer: *mut (dyn FnMut(Event) + 'static),
+    start: usize,
+    end: usize,
+    size: usize,
+    buffer_start: usize,
+    buffer_end: usize,
+}
+
+impl Tracer {
+    fn slot<T>(&self, value: *const T) -> Slot {
+        let address = value as usize;
+
+        if (self.start..self.end).contains(&address) {
+            Slot::Array((address - self.start) / self.size)
+        } else if (self.buffer_start..self.buffer_end).contains(&address) {
+            Slot::Buffer((address - self.buffer_start) / self.size)
+        } else {
+            Slot::Other
+        }
+    }
+
+    /// Index of the first value of `array`, if it lies in the traced array.
+    fn index<T>(&self, array: &[T]) -> Option<usize> {
+        let address = array.as_ptr() as usize;
+
+        if (self.start..self.end).contains(&address) {
+            Some((address - self.start) / self.size)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+thread_local! {
+    static TRACER: Cell<Option<Tracer>> = const { Cell::new(None) };
+}
+
+#[cfg(feature = "trace")]
+struct Scope {
+    previous: Option<Tracer>,
+}
+
+#[cfg(feature = "trace")]
+impl Drop for Scope {
+    fn drop(&mut self) {
+        TRACER.with(|cell| cell.set(self.previous));
+    }
+}
+
+/// Sends the event built from the current tracer,
+/// if any traces values of `size` bytes.
+#[inline]
+fn emit<B>(size: usize, build: B)
+where
+    B: FnOnce(&Tracer) -> Option<Event>,
+{
+    #[cfg(feature = "trace")]
+    TRACER.with(|cell| {
+        // Taken out while the observer runs, so that it can't be reentered
+        if let Some(tracer) = cell.take() {
+            if tracer.size == size {
+                if let Some(event) = build(&tracer) {
+                    // SAFETY: `trace_by` keeps the observer alive while it is set
+                    unsafe { (*tracer.observer)(event) };
+                }
+            }
+
+            cell.set(Some(tracer));
+        }
+    });
+
+    #[cfg(not(feature = "trace"))]
+    let _ = (size, build);
+}
+
+#[inline]
+pub(crate) fn swap<T>(array: &[T], i: usize, j: usize) {
+    emit(mem::size_of::<T>(), |tracer| {
+        let start = tracer.index(array)?;
+        Some(Event::Swap(start + i, start + j))
+    });
+}
+
+/// `count` values were copied from `from` to `to`.
+#[inline]
+pub(crate) fn write<T>(to: *const T, from: *const T, count: usize) {
+    for k in 0..count {
+        emit(mem::size_of::<T>(), |tracer| {
+            Some(Event::Write {
+                to: tracer.slot(to.wrapping_add(k)),
+                from: tracer.slot(from.wrapping_add(k)),
+            })
+        });
+    }
+}
+
+/// Sets the scratch buffer of `len` values the following writes refer to.
+#[inline]
+pub(crate) fn buffer<T>(buffer: *const T, len: usize) {
+    #[cfg(feature = "trace")]
+    TRACER.with(|cell| {
+        if let Some(mut tracer) = cell.get() {
+            tracer.buffer_start = buffer as usize;
+            tracer.buffer_end = buffer as usize + len * mem::size_of::<T>();
+            cell.set(Some(tracer));
+        }
+    });
+
+    #[cfg(not(feature = "trace"))]
+    let _ = (buffer, len);
+}
+
+#[inline]
+pub(crate) fn partition<T>(array: &[T], less: usize, greater: usize) {
+    emit(mem::size_of::<T>(), |tracer| {
+        let start = tracer.index(array)?;
+
+        Some(Event::Partition {
+            start,
+            less: start + less,
+            greater: start + greater,
+            end: start + array.len(),
+        })
+    });
+}
+
+#[inline]
+pub(crate) fn heapify<T>(array: &[T], root: usize, size: usize) {
+    emit(mem::size_of::<T>(), |tracer| {
+        Some(Event::Heapify {
+            start: tracer.index(array)?,
+            root,
+            size,
+        })
+    });
+}
+
+#[cfg(all(
+    test,
+    feature = "trace",
+    feature = "bubble",
+    feature = "heap",
+    feature = "merge",
+    feature = "quick",
+))]
+mod tests {
+    use super::*;
+    use crate::sort::bubble::BubbleSort;
+    use crate::sort::heap::HeapSort;
+    use crate::sort::merge::{MergeSort, TimSort};
+    use crate::sort::quick::QuickSort;
+    use std::cell::RefCell;
+
+    /// Replays `events` on `array`, checking that every comparison
+    /// saw the values that `compared` says the comparator got.
+    fn replay(array: &mut Vec<u32>, events: &[Event], compared: &[(u32, u32)]) {
+        let mut buffer = vec![0; array.len()];
+        let mut compared = compared.iter();
+
+        for &event in events {
+            let get = |array: &Vec<u32>, buffer: &Vec<u32>, slot| match slot {
+                Slot::Array(index) => array[index],
+                Slot::Buffer(index) => buffer[index],
+                Slot::Other => panic!("untracked value"),
+            };
+
+            match event {
+                Event::Compare(a, b) => {
+                    let values = (get(array, &buffer, a), get(array, &buffer, b));
+                    assert_eq!(Some(&values), compared.next());
+                }
+                Event::Swap(i, j) => array.swap(i, j),
+                Event::Write { to, from } => {
+                    let value = get(array, &buffer, from);
+
+                    match to {
+                        Slot::Array(index) => array[index] = value,
+                        Slot::Buffer(index) => buffer[index] = value,
+                        Slot::Other => panic!("untracked value"),
+                    }
+                }
+                Event::Partition { .. } | Event::Heapify { .. } => (),
+            }
+        }
+
+        assert_eq!(compared.next(), None);
+    }
+
+    fn check<S>(sorter: S)
+    where
+        S: Sorter,
+    {
+        let input: Vec<u32> = (0..200).map(|i| i * 7919 % 211).collect();
+        let mut array = input.clone();
+        let compared = RefCell::new(Vec::new());
+        let mut events = Vec::new();
+
+        trace_by(
+            sorter,
+            &mut array,
+            |a: &u32, b: &u32| {
+                compared.borrow_mut().push((*a, *b));
+                a.cmp(b)
+            },
+            |event| events.push(event),
+        );
+
+        let mut replayed = input;
+        replay(&mut replayed, &events, &compared.borrow());
+
+        assert_eq!(replayed, array);
+        assert!(array.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn trace_replay_test_1() {
+        check(BubbleSort);
+        check(QuickSort);
+        check(MergeSort);
+        check(HeapSort);
+    }
+
+    #[test]
+    fn trace_events_test_1() {
+        let mut array: Vec<u32> = (0..100).rev().collect();
+        let mut events = Vec::new();
+        trace(QuickSort, &mut array, |event| events.push(event));
+
+        // The middle value is swapped to the front as the first pivot
+        assert_eq!(events[0], Event::Swap(0, 50));
+        assert!(events.contains(&Event::Partition {
+            start: 0,
+            less: 49,
+            greater: 50,
+            end: 100
+        }));
+
+        let mut events = Vec::new();
+        trace(HeapSort, &mut [1, 2, 3], |event| events.push(event));
+
+        assert_eq!(
+            events[0],
+            Event::Heapify {
+                start: 0,
+                root: 0,
+                size: 3
+            }
+        );
+        assert!(events.contains(&Event::Swap(0, 2)));
+    }
+
+    #[test]
+    fn trace_untracked_test_1() {
+        let input: Vec<u32> = (0..500).map(|i| i * 7919 % 503).collect();
+        let mut array = input.clone();
+        let mut slots = Vec::new();
+
+        // Merges in a buffer of its own it doesn't report
+        trace(TimSort, &mut array, |event| {
+            if let Event::Compare(a, b) = event {
+                slots.extend([a, b]);
+            }
+        });
+
+        assert!(slots.contains(&Slot::Other));
+        assert!(slots.iter().all(|slot| match slot {
+            Slot::Array(index) => *index < input.len(),
+            Slot::Buffer(_) => false,
+            Slot::Other => true,
+        }));
+
+        let mut recorder = Recorder::new(Vec::new());
+        recorder.record(Event::Compare(Slot::Array(0), Slot::Other));
+        let output = String::from_utf8(recorder.finish().unwrap()).unwrap();
+
+        assert_eq!(
+            output,
+            "{\"event\":\"compare\",\"a\":{\"array\":0},\"b\":\"other\"}\n"
+        );
+    }
+
+    #[test]
+    fn recorder_test_1() {
+        let mut recorder = Recorder::new(Vec::new());
+        trace(BubbleSort, &mut [2, 1], |event| recorder.record(event));
+        let output = String::from_utf8(recorder.finish().unwrap()).unwrap();
+
+        assert_eq!(
+            output,
+            "{\"event\":\"compare\",\"a\":{\"array\":0},\"b\":{\"array\":1}}\n\
+             {\"event\":\"swap\",\"i\":0,\"j\":1}\n"
+        );
+    }
+}
@@ -10,5 +10,23 @@ pub mod kmp;
 #[cfg(feature = "trie")]
 pub mod trie;
 
+#[cfg(feature = "aho_corasick")]
+pub mod aho_corasick;
+
 #[cfg(feature = "suffix_automaton")]
 pub mod suffix_automaton;
+
+#[cfg(feature = "suffix_array")]
+pub mod suffix_array;
+
+#[cfg(feature = "suffix_tree")]
+pub mod suffix_tree;
+
+#[cfg(feature = "generalized_suffix_automaton")]
+pub mod generalized_suffix_automaton;
+
+#[cfg(feature = "hash")]
+pub mod hash;
+
+#[cfg(feature = "palindrome")]
+pub mod palindrome;
@@ -0,0 +1,361 @@
+use std::collections::VecDeque;
+
+use crate::string::trie::{Trie, ROOT};
+
+static NO_LINK: usize = usize::MAX;
+
+/// Which matches `AhoCorasick::find_all` reports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// Every occurrence of every pattern, overlapping ones included.
+    #[default]
+    All,
+    /// Scanning left to right, the longest of the matches starting
+    /// leftmost, then the same after its end. Matches don't overlap.
+    LeftmostLongest,
+}
+
+/// Occurrence of `patterns[pattern]` at `text[start..end]`, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Finds many patterns at once in a single pass over the text.
+/// The patterns are kept in a `Trie`, whose nodes get failure links
+/// to the longest proper suffix that is also in the trie, and output
+/// links to the longest such suffix that is a whole pattern.
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    trie: Trie,
+    match_kind: MatchKind,
+    /// Length in bytes of every pattern.
+    lengths: Vec<usize>,
+    /// Patterns ending at every node, several if some are equal.
+    outputs: Vec<Vec<usize>>,
+    /// Length in bytes of the string of every node.
+    depths: Vec<usize>,
+    fail_links: Vec<usize>,
+    output_links: Vec<usize>,
+}
+
+impl AhoCorasick {
+    /// Patterns are numbered in the order they are given.
+    /// Panics if one of them is empty.\
+    /// O(m) time, m = total length of the patterns\
+    /// O(m) space
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut trie = Trie::new();
+        let mut lengths = Vec::new();
+        let mut ends = Vec::new();
+
+        for pattern in patterns {
+            let pattern = pattern.as_ref();
+            assert!(!pattern.is_empty(), "empty pattern");
+
+            ends.push(trie.insert(pattern));
+            lengths.push(pattern.len());
+        }
+
+        let mut outputs = vec![Vec::new(); trie.node_count()];
+
+        for (pattern, &end) in ends.iter().enumerate() {
+            outputs[end].push(pattern);
+        }
+
+        let mut ret = Self {
+            trie,
+            match_kind: MatchKind::All,
+            lengths,
+            outputs,
+            depths: Vec::new(),
+            fail_links: Vec::new(),
+            output_links: Vec::new(),
+        };
+        ret.build_links();
+
+        ret
+    }
+
+    pub fn with_match_kind(mut self, match_kind: MatchKind) -> Self {
+        self.match_kind = match_kind;
+        self
+    }
+
+    pub fn match_kind(&self) -> MatchKind {
+        self.match_kind
+    }
+
+    pub fn pattern_count(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Matches in `text` ordered by their end, then longest first
+    /// for `MatchKind::All`, by their start for `MatchKind::LeftmostLongest`.\
+    /// O(n + k) time for `MatchKind::All`, k = number of overlapping matches\
+    /// O(n + r * L) time for `MatchKind::LeftmostLongest`, r = number of matches,
+    /// L = length of the longest pattern
+    pub fn find_all(&self, text: &str) -> Vec<Match> {
+        match self.match_kind {
+            MatchKind::All => self.find_overlapping(text),
+            MatchKind::LeftmostLongest => self.find_leftmost_longest(text),
+        }
+    }
+
+    /// Whether any pattern occurs in `text`.\
+    /// O(n) time
+    pub fn is_match(&self, text: &str) -> bool {
+        let mut vertex = ROOT;
+
+        text.chars().any(|c| {
+            vertex = self.step(vertex, c);
+            self.output(vertex) != NO_LINK
+        })
+    }
+
+    fn find_overlapping(&self, text: &str) -> Vec<Match> {
+        let mut matches = Vec::new();
+        let mut vertex = ROOT;
+
+        for (i, c) in text.char_indices() {
+            vertex = self.step(vertex, c);
+            let end = i + c.len_utf8();
+            let mut output = self.output(vertex);
+
+            while output != NO_LINK {
+                for &pattern in &self.outputs[output] {
+                    matches.push(Match {
+                        pattern,
+                        start: end - self.lengths[pattern],
+                        end,
+                    });
+                }
+
+                output = self.output_links[output];
+            }
+        }
+
+        matches
+    }
+
+    /// Scans until no later match can start as far left as the best one
+    /// so far, reports it and scans again from its end.
+    fn find_leftmost_longest(&self, text: &str) -> Vec<Match> {
+        let mut matches = Vec::new();
+        let mut offset = 0;
+
+        loop {
+            let mut vertex = ROOT;
+            let mut best: Option<Match> = None;
+
+            for (i, c) in text[offset..].char_indices() {
+                vertex = self.step(vertex, c);
+                let end = offset + i + c.len_utf8();
+
+                // Matches ending here or later start no earlier than the string of `vertex`
+                if best.is_some_and(|best| end - self.depths[vertex] > best.start) {
+                    break;
+                }
+
+                // The longest pattern ending here starts leftmost,
+                // equal ones go to the first pattern
+                let output = self.output(vertex);
+
+                if output != NO_LINK {
+                    let pattern = self.outputs[output][0];
+                    let start = end - self.lengths[pattern];
+
+                    if best.map_or(true, |best| {
+                        start < best.start || start == best.start && end > best.end
+                    }) {
+                        best = Some(Match {
+                            pattern,
+                            start,
+                            end,
+                        });
+                    }
+                }
+            }
+
+            match best {
+                Some(best) => {
+                    matches.push(best);
+                    offset = best.end;
+                }
+                None => return matches,
+            }
+        }
+    }
+
+    /// Node of the longest pattern ending at `vertex`, if any.
+    fn output(&self, vertex: usize) -> usize {
+        if self.outputs[vertex].is_empty() {
+            self.output_links[vertex]
+        } else {
+            vertex
+        }
+    }
+
+    /// Follows failure links until `c` can be matched, or up to the root.
+    fn step(&self, mut vertex: usize, c: char) -> usize {
+        loop {
+            if let Some(next) = self.trie.next(vertex, c) {
+                return next;
+            }
+
+            if vertex == ROOT {
+                return ROOT;
+            }
+
+            vertex = self.fail_links[vertex];
+        }
+    }
+
+    /// Breadth first, so the links of shorter strings are known first.
+    fn build_links(&mut self) {
+        let node_count = self.trie.node_count();
+        self.fail_links = vec![ROOT; node_count];
+        self.output_links = vec![NO_LINK; node_count];
+        self.depths = vec![0; node_count];
+
+        let mut queue = VecDeque::from([ROOT]);
+
+        while let Some(vertex) = queue.pop_front() {
+            let children: Vec<(char, usize)> = self.trie.children(vertex).collect();
+
+            for (c, child) in children {
+                self.depths[child] = self.depths[vertex] + c.len_utf8();
+
+                if vertex != ROOT {
+                    let fail = self.step(self.fail_links[vertex], c);
+                    self.fail_links[child] = fail;
+
+                    self.output_links[child] = if self.outputs[fail].is_empty() {
+                        self.output_links[fail]
+                    } else {
+                        fail
+                    };
+                }
+
+                queue.push_back(child);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(patterns: &[&str], text: &str) -> Vec<Match> {
+        let mut matches = Vec::new();
+
+        for (pattern, needle) in patterns.iter().enumerate() {
+            for start in (0..text.len()).filter(|&start| text.is_char_boundary(start)) {
+                if text[start..].starts_with(needle) {
+                    let end = start + needle.len();
+                    matches.push(Match {
+                        pattern,
+                        start,
+                        end,
+                    });
+                }
+            }
+        }
+
+        matches
+    }
+
+    fn triples(matches: Vec<Match>) -> Vec<(usize, usize, usize)> {
+        matches
+            .into_iter()
+            .map(|m| (m.pattern, m.start, m.end))
+            .collect()
+    }
+
+    #[test]
+    fn aho_corasick_unit_1() {
+        let patterns = ["he", "she", "his", "hers"];
+        let automaton = AhoCorasick::new(patterns);
+
+        assert_eq!(
+            triples(automaton.find_all("ushers")),
+            [(1, 1, 4), (0, 2, 4), (3, 2, 6)]
+        );
+        assert!(automaton.is_match("ahishe"));
+        assert!(!automaton.is_match("hi sh"));
+    }
+
+    #[test]
+    fn aho_corasick_naive_unit_1() {
+        let patterns = ["a", "ab", "bab", "bc", "bca", "c", "caa", "ёж", "жи", "ж"];
+        let text = "abccab ёжикabcaab ежи bcaa".repeat(3);
+        let automaton = AhoCorasick::new(patterns);
+
+        let mut found = automaton.find_all(&text);
+        let mut expected = naive(&patterns, &text);
+        found.sort_by_key(|m| (m.start, m.pattern));
+        expected.sort_by_key(|m| (m.start, m.pattern));
+
+        assert_eq!(found, expected);
+        assert!(found
+            .iter()
+            .all(|m| text[m.start..m.end] == *patterns[m.pattern]));
+    }
+
+    #[test]
+    fn aho_corasick_leftmost_longest_unit_1() {
+        let patterns = ["abcd", "b", "bcd", "abc", "b"];
+        let automaton = AhoCorasick::new(patterns).with_match_kind(MatchKind::LeftmostLongest);
+
+        assert_eq!(
+            triples(automaton.find_all("xabcdbcdbe")),
+            [(0, 1, 5), (2, 5, 8), (1, 8, 9)]
+        );
+    }
+
+    #[test]
+    fn aho_corasick_leftmost_longest_naive_unit_1() {
+        let patterns = [
+            "a", "ab", "bab", "bc", "bca", "c", "caa", "ёж", "жи", "ж", "abccab",
+        ];
+        let text = "abccab ёжикabcaab ежи bcaa".repeat(3);
+        let automaton = AhoCorasick::new(patterns).with_match_kind(MatchKind::LeftmostLongest);
+
+        // Longest of the leftmost overlapping matches, then again after it
+        let mut overlapping = naive(&patterns, &text);
+        overlapping.sort_by_key(|m| (m.start, std::cmp::Reverse(m.end), m.pattern));
+
+        let mut expected: Vec<Match> = Vec::new();
+
+        for m in overlapping {
+            if expected.last().map_or(0, |last| last.end) <= m.start {
+                expected.push(m);
+            }
+        }
+
+        assert_eq!(automaton.find_all(&text), expected);
+
+        // Every prefix of a long run matches at every position
+        let patterns: Vec<String> = (1..=50).map(|length| "a".repeat(length)).collect();
+        let automaton = AhoCorasick::new(&patterns).with_match_kind(MatchKind::LeftmostLongest);
+        let found = automaton.find_all(&"a".repeat(1020));
+
+        assert_eq!(found.len(), 21);
+        assert!(found[..20].iter().all(|m| m.pattern == 49));
+        assert_eq!(
+            found[20],
+            Match {
+                pattern: 19,
+                start: 1000,
+                end: 1020
+            }
+        );
+    }
+}
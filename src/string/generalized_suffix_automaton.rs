@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+
+static ROOT: usize = 0;
+static NO_LINK: usize = usize::MAX;
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    length: usize,
+    link: usize,
+    next: HashMap<char, usize>,
+    /// State and char the longest string of this state extends.
+    parent: (usize, char),
+    /// Number of input strings the state's strings occur in.
+    strings: usize,
+    /// Last input string counted in `strings`.
+    last_string: usize,
+}
+
+/// Suffix automaton of several strings at once. Unlike adding them one
+/// after another to a `SuffixAutomaton`, every string starts over from
+/// the root, so no substring spans two strings.
+#[derive(Debug, Clone)]
+pub struct GeneralizedSuffixAutomaton {
+    states: Vec<State>,
+    string_count: usize,
+}
+
+impl Default for GeneralizedSuffixAutomaton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeneralizedSuffixAutomaton {
+    pub fn new() -> Self {
+        let root = State {
+            link: NO_LINK,
+            last_string: NO_LINK,
+            ..State::default()
+        };
+
+        Self {
+            states: vec![root],
+            string_count: 0,
+        }
+    }
+
+    /// O(n sqrt n) time, n = total length\
+    /// O(n) space
+    pub fn build<I, S>(strings: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut ret = GeneralizedSuffixAutomaton::new();
+
+        for string in strings {
+            ret.add_string(string.as_ref());
+        }
+
+        ret
+    }
+
+    /// Adds `string` and returns its index.
+    pub fn add_string(&mut self, string: &str) -> usize {
+        let index = self.string_count;
+        self.string_count += 1;
+
+        let mut last = ROOT;
+
+        for sym in string.chars() {
+            last = self.add_char(last, sym);
+
+            // Every suffix of the prefix read so far occurs in this string,
+            // the shorter ones are already counted once one of them is
+            let mut cur = last;
+
+            while cur != ROOT && self.states[cur].last_string != index {
+                self.states[cur].last_string = index;
+                self.states[cur].strings += 1;
+                cur = self.states[cur].link;
+            }
+        }
+
+        index
+    }
+
+    pub fn string_count(&self) -> usize {
+        self.string_count
+    }
+
+    /// Whether `string` is a substring of one of the strings.\
+    /// O(m) time
+    pub fn contains(&self, string: &str) -> bool {
+        self.find(string).is_some()
+    }
+
+    /// Number of strings that `pattern` is a substring of.\
+    /// O(m) time
+    pub fn count_strings(&self, pattern: &str) -> usize {
+        match self.find(pattern) {
+            Some(state) if state == ROOT => self.string_count,
+            Some(state) => self.states[state].strings,
+            None => 0,
+        }
+    }
+
+    /// Number of distinct non-empty substrings of all the strings.\
+    /// O(n) time
+    pub fn distinct_substrings(&self) -> usize {
+        self.states
+            .iter()
+            .skip(1)
+            .map(|state| state.length - self.states[state.link].length)
+            .sum()
+    }
+
+    /// Longest substring of at least `count` of the strings,
+    /// `None` if there is no such non-empty substring.\
+    /// O(n) time
+    pub fn longest_common_substring(&self, count: usize) -> Option<String> {
+        let best = (1..self.states.len())
+            .filter(|&state| self.states[state].strings >= count)
+            .max_by_key(|&state| self.states[state].length)?;
+
+        let mut substring: Vec<char> = Vec::with_capacity(self.states[best].length);
+        let mut cur = best;
+
+        while cur != ROOT {
+            let (parent, sym) = self.states[cur].parent;
+            substring.push(sym);
+            cur = parent;
+        }
+
+        Some(substring.into_iter().rev().collect())
+    }
+
+    fn find(&self, string: &str) -> Option<usize> {
+        let mut cur = ROOT;
+
+        for sym in string.chars() {
+            cur = *self.states[cur].next.get(&sym)?;
+        }
+
+        Some(cur)
+    }
+
+    fn new_state(&mut self, length: usize, parent: (usize, char)) -> usize {
+        self.states.push(State {
+            length,
+            link: ROOT,
+            parent,
+            last_string: NO_LINK,
+            ..State::default()
+        });
+
+        self.states.len() - 1
+    }
+
+    /// Extends the prefix ending at `last` by `sym`, returns the state of
+    /// the longer prefix. If another string already made that transition,
+    /// the existing state is reused or split instead of adding a new one.\
+    /// O(1) time (amortized)
+    fn add_char(&mut self, last: usize, sym: char) -> usize {
+        if let Some(&next) = self.states[last].next.get(&sym) {
+            if self.states[last].length + 1 == self.states[next].length {
+                return next;
+            }
+
+            return self.clone_state(last, next, sym);
+        }
+
+        let cur_state = self.new_state(self.states[last].length + 1, (last, sym));
+        let mut prev = last;
+
+        while prev != NO_LINK && !self.states[prev].next.contains_key(&sym) {
+            self.states[prev].next.insert(sym, cur_state);
+            prev = self.states[prev].link;
+        }
+
+        if prev != NO_LINK {
+            let next = self.states[prev].next[&sym];
+
+            self.states[cur_state].link =
+                if self.states[prev].length + 1 == self.states[next].length {
+                    next
+                } else {
+                    self.clone_state(prev, next, sym)
+                };
+        }
+
+        cur_state
+    }
+
+    /// Splits the strings of `next` no longer than `prev`'s longest plus
+    /// `sym` into a new state, and redirects the transitions to them.
+    fn clone_state(&mut self, mut prev: usize, next: usize, sym: char) -> usize {
+        let cloned = self.new_state(self.states[prev].length + 1, (prev, sym));
+
+        // The shorter strings occur wherever the longer ones do
+        self.states[cloned].next = self.states[next].next.clone();
+        self.states[cloned].link = self.states[next].link;
+        self.states[cloned].strings = self.states[next].strings;
+        self.states[cloned].last_string = self.states[next].last_string;
+
+        while prev != NO_LINK && self.states[prev].next.get(&sym) == Some(&next) {
+            self.states[prev].next.insert(sym, cloned);
+            prev = self.states[prev].link;
+        }
+
+        self.states[next].link = cloned;
+
+        cloned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    fn substrings(string: &str) -> HashSet<String> {
+        let chars: Vec<char> = string.chars().collect();
+
+        (0..chars.len())
+            .flat_map(|start| (start + 1..=chars.len()).map(move |end| (start, end)))
+            .map(|(start, end)| chars[start..end].iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn generalized_suffix_automaton_unit_1() {
+        let strings = ["the", "a", "there", "answer", "any", "by", "bye", "their"];
+        let automaton = GeneralizedSuffixAutomaton::build(strings);
+
+        assert!(automaton.contains("the"));
+        assert!(automaton.contains("their"));
+        assert!(!automaton.contains("ea"));
+        assert!(!automaton.contains("thea"));
+
+        let all: HashSet<String> = strings
+            .iter()
+            .flat_map(|string| substrings(string))
+            .collect();
+
+        assert_eq!(automaton.distinct_substrings(), all.len());
+
+        for substring in &all {
+            let expected = strings
+                .iter()
+                .filter(|string| string.contains(substring.as_str()))
+                .count();
+
+            assert_eq!(
+                automaton.count_strings(substring),
+                expected,
+                "{}",
+                substring
+            );
+        }
+    }
+
+    #[test]
+    fn generalized_suffix_automaton_lcs_unit_1() {
+        let strings = ["xabxac", "abcabxabcd", "bxabc", "ёжикbx"];
+        let automaton = GeneralizedSuffixAutomaton::build(strings);
+
+        assert_eq!(automaton.longest_common_substring(4).as_deref(), Some("bx"));
+        assert_eq!(
+            automaton.longest_common_substring(2).as_deref(),
+            Some("bxabc")
+        );
+        assert_eq!(
+            automaton.longest_common_substring(1).as_deref(),
+            Some("abcabxabcd")
+        );
+        assert_eq!(automaton.longest_common_substring(5), None);
+
+        // "bxa" and "xab" are both common to the first three
+        let common = automaton.longest_common_substring(3).unwrap();
+        assert_eq!(common.len(), 3);
+        assert_eq!(automaton.count_strings(&common), 3);
+        assert_eq!(automaton.count_strings(""), 4);
+    }
+}
@@ -0,0 +1,429 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::Range;
+
+/// Mersenne prime 2^61 - 1.
+static DEFAULT_MODULUS: u64 = (1 << 61) - 1;
+static DEFAULT_BASE: u64 = 0x1F2E_3D4C_5B6A;
+/// Mersenne prime 2^31 - 1.
+static SECOND_MODULUS: u64 = (1 << 31) - 1;
+static SECOND_BASE: u64 = 1_000_003;
+
+/// Polynomial hash of every substring of a text,
+/// `h(s) = s[0] * base^(k-1) + ... + s[k-1] mod modulus`,
+/// where every symbol counts as its value mod `modulus - 1`, plus one.
+/// Equal substrings have equal hashes. Different ones of length k collide
+/// with probability at most about k / modulus over a random base, see
+/// `with_random_base`. A fixed base is deterministic, but inputs can be
+/// built to collide on purpose.
+#[derive(Debug, Clone)]
+pub struct PolynomialHash {
+    modulus: u64,
+    base: u64,
+    /// Hash of every prefix, the empty one included.
+    prefixes: Vec<u64>,
+    powers: Vec<u64>,
+}
+
+impl PolynomialHash {
+    /// Modulus 2^61 - 1 with a fixed base.\
+    /// O(n) time\
+    /// O(n) space
+    pub fn new<T>(text: &[T]) -> Self
+    where
+        T: Copy + Into<u64>,
+    {
+        Self::with_params(text, DEFAULT_MODULUS, DEFAULT_BASE)
+    }
+
+    /// Base chosen at random in `2..modulus`, so no input collides
+    /// more often than the bound above. Panics unless `modulus > 2`.\
+    /// O(n) time\
+    /// O(n) space
+    pub fn with_random_base<T>(text: &[T], modulus: u64) -> Self
+    where
+        T: Copy + Into<u64>,
+    {
+        Self::with_params(text, modulus, random_base(modulus))
+    }
+
+    /// Panics unless `1 < base < modulus`.\
+    /// O(n) time\
+    /// O(n) space
+    pub fn with_params<T>(text: &[T], modulus: u64, base: u64) -> Self
+    where
+        T: Copy + Into<u64>,
+    {
+        assert!(1 < base && base < modulus, "base must be in 2..modulus");
+
+        let mut prefixes = Vec::with_capacity(text.len() + 1);
+        let mut powers = Vec::with_capacity(text.len() + 1);
+        prefixes.push(0);
+        powers.push(1);
+
+        for (i, &sym) in text.iter().enumerate() {
+            let value = symbol(sym, modulus);
+
+            prefixes.push(mul_add_mod(prefixes[i], base, value, modulus));
+            powers.push(mul_mod(powers[i], base, modulus));
+        }
+
+        Self {
+            modulus,
+            base,
+            prefixes,
+            powers,
+        }
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    pub fn len(&self) -> usize {
+        self.prefixes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Hash of `text[range]`.\
+    /// O(1) time
+    pub fn hash(&self, range: Range<usize>) -> u64 {
+        assert!(range.start <= range.end && range.end <= self.len());
+
+        let shifted = mul_mod(
+            self.prefixes[range.start],
+            self.powers[range.end - range.start],
+            self.modulus,
+        );
+
+        let prefix = self.prefixes[range.end];
+
+        // Both are below the modulus, which may not leave room to add it
+        if prefix >= shifted {
+            prefix - shifted
+        } else {
+            self.modulus - (shifted - prefix)
+        }
+    }
+
+    /// Hash of another sequence with the same modulus and base,
+    /// comparable to the ones of `hash`.\
+    /// O(m) time
+    pub fn hash_of<T>(&self, pattern: &[T]) -> u64
+    where
+        T: Copy + Into<u64>,
+    {
+        pattern.iter().fold(0, |hash, &sym| {
+            mul_add_mod(hash, self.base, symbol(sym, self.modulus), self.modulus)
+        })
+    }
+}
+
+/// Two independent `PolynomialHash`es of the same text,
+/// so a false match needs both of them to collide.
+#[derive(Debug, Clone)]
+pub struct DoubleHash {
+    first: PolynomialHash,
+    second: PolynomialHash,
+}
+
+impl DoubleHash {
+    /// Moduli 2^61 - 1 and 2^31 - 1 with fixed bases.\
+    /// O(n) time\
+    /// O(n) space
+    pub fn new<T>(text: &[T]) -> Self
+    where
+        T: Copy + Into<u64>,
+    {
+        Self::with_params(
+            text,
+            (DEFAULT_MODULUS, DEFAULT_BASE),
+            (SECOND_MODULUS, SECOND_BASE),
+        )
+    }
+
+    /// Moduli 2^61 - 1 and 2^31 - 1 with random bases,
+    /// see `PolynomialHash::with_random_base`.\
+    /// O(n) time\
+    /// O(n) space
+    pub fn with_random_bases<T>(text: &[T]) -> Self
+    where
+        T: Copy + Into<u64>,
+    {
+        Self {
+            first: PolynomialHash::with_random_base(text, DEFAULT_MODULUS),
+            second: PolynomialHash::with_random_base(text, SECOND_MODULUS),
+        }
+    }
+
+    /// Both params are `(modulus, base)`, see `PolynomialHash::with_params`.\
+    /// O(n) time\
+    /// O(n) space
+    pub fn with_params<T>(text: &[T], first: (u64, u64), second: (u64, u64)) -> Self
+    where
+        T: Copy + Into<u64>,
+    {
+        Self {
+            first: PolynomialHash::with_params(text, first.0, first.1),
+            second: PolynomialHash::with_params(text, second.0, second.1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.first.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.first.is_empty()
+    }
+
+    /// O(1) time
+    pub fn hash(&self, range: Range<usize>) -> (u64, u64) {
+        (self.first.hash(range.clone()), self.second.hash(range))
+    }
+
+    /// O(m) time
+    pub fn hash_of<T>(&self, pattern: &[T]) -> (u64, u64)
+    where
+        T: Copy + Into<u64>,
+    {
+        (self.first.hash_of(pattern), self.second.hash_of(pattern))
+    }
+
+    /// Whether `text[first]` and `text[second]` are equal, up to collisions.
+    /// Build with `with_random_bases` if the text may be adversarial.\
+    /// O(1) time
+    pub fn equal(&self, first: Range<usize>, second: Range<usize>) -> bool {
+        first.len() == second.len() && self.hash(first) == self.hash(second)
+    }
+
+    /// Length of the longest common prefix of `text[first]` and `text[second]`,
+    /// up to collisions, as for `equal`.\
+    /// O(log n) time
+    pub fn lcp(&self, first: Range<usize>, second: Range<usize>) -> usize {
+        // Invariant: prefixes of length low are equal, of length high + 1 are not
+        let mut low = 0;
+        let mut high = first.len().min(second.len());
+
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+
+            if self.equal(
+                first.start..first.start + mid,
+                second.start..second.start + mid,
+            ) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        low
+    }
+}
+
+/// Start of every occurrence of every pattern in `text`, in one list per
+/// pattern. An empty pattern occurs at every position. Candidates are
+/// checked symbol by symbol, so collisions never give false matches.\
+/// O(n * l + m + k * L) time, l = number of distinct pattern lengths,
+/// m = total length of the patterns, k = number of candidates, L = their length\
+/// O(n + m) space
+pub fn rabin_karp<T, P>(patterns: &[P], text: &[T]) -> Vec<Vec<usize>>
+where
+    T: Copy + Eq + Into<u64>,
+    P: AsRef<[T]>,
+{
+    let hashes = DoubleHash::new(text);
+    let mut answer = vec![Vec::new(); patterns.len()];
+
+    // Patterns by length, then by hash
+    let mut by_length: HashMap<usize, HashMap<(u64, u64), Vec<usize>>> = HashMap::new();
+
+    for (index, pattern) in patterns.iter().enumerate() {
+        let pattern = pattern.as_ref();
+
+        if pattern.len() <= text.len() {
+            by_length
+                .entry(pattern.len())
+                .or_default()
+                .entry(hashes.hash_of(pattern))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    for (length, by_hash) in by_length {
+        for start in 0..=text.len() - length {
+            let Some(candidates) = by_hash.get(&hashes.hash(start..start + length)) else {
+                continue;
+            };
+
+            for &index in candidates {
+                if patterns[index].as_ref() == &text[start..start + length] {
+                    answer[index].push(start);
+                }
+            }
+        }
+    }
+
+    answer
+}
+
+/// `rabin_karp` over the bytes of the strings, returns byte offsets into `text`.\
+/// O(n * l + m + k * L) time\
+/// O(n + m) space
+pub fn rabin_karp_str(patterns: &[&str], text: &str) -> Vec<Vec<usize>> {
+    let patterns: Vec<&[u8]> = patterns.iter().map(|pattern| pattern.as_bytes()).collect();
+    let mut answer = rabin_karp(&patterns, text.as_bytes());
+
+    // An empty pattern matches between the bytes of a char too
+    for (pattern, offsets) in patterns.iter().zip(&mut answer) {
+        if pattern.is_empty() {
+            offsets.retain(|&offset| text.is_char_boundary(offset));
+        }
+    }
+
+    answer
+}
+
+/// Symbols land in `1..modulus`, never on 0, so leading ones always
+/// change the hash. Symbols equal mod `modulus - 1` hash alike.
+fn symbol<T>(sym: T, modulus: u64) -> u64
+where
+    T: Into<u64>,
+{
+    sym.into() % (modulus - 1) + 1
+}
+
+/// In 128 bits, so any modulus up to `u64::MAX` works.
+fn mul_mod(first: u64, second: u64, modulus: u64) -> u64 {
+    mul_add_mod(first, second, 0, modulus)
+}
+
+fn mul_add_mod(first: u64, second: u64, add: u64, modulus: u64) -> u64 {
+    ((first as u128 * second as u128 + add as u128) % modulus as u128) as u64
+}
+
+/// Seeded by the random keys std gives every `RandomState`.
+fn random_base(modulus: u64) -> u64 {
+    assert!(modulus > 2, "modulus must be greater than 2");
+
+    let random = RandomState::new().build_hasher().finish();
+
+    2 + random % (modulus - 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(pattern: &str, text: &str) -> Vec<usize> {
+        (0..=text.len())
+            .filter(|&start| text.is_char_boundary(start) && text[start..].starts_with(pattern))
+            .collect()
+    }
+
+    #[test]
+    fn hash_unit_1() {
+        let text: Vec<char> = "abacabaёжabacaba\0a".chars().collect();
+        let hashes = DoubleHash::new(&text);
+        let single = PolynomialHash::with_params(&text, 1_000_000_007, 131);
+
+        assert_eq!(hashes.len(), text.len());
+
+        for first in 0..text.len() {
+            for second in 0..text.len() {
+                for length in 0..=text.len() - first.max(second) {
+                    let (first, second) = (first..first + length, second..second + length);
+                    let equal = text[first.clone()] == text[second.clone()];
+
+                    assert_eq!(hashes.equal(first.clone(), second.clone()), equal);
+                    assert_eq!(single.hash(first.clone()) == single.hash(second), equal);
+                    assert_eq!(hashes.hash(first.clone()), hashes.hash_of(&text[first]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hash_large_modulus_unit_1() {
+        // 2^64 - 59, the largest prime below 2^64
+        let modulus = u64::MAX - 58;
+        let text: Vec<u32> = (0..300)
+            .map(|i| i * 7919 % 13)
+            .chain([u32::MAX; 3])
+            .collect();
+        let hashes = [
+            PolynomialHash::with_params(&text, modulus, modulus - 1),
+            PolynomialHash::with_params(&text, modulus, 1 << 63),
+            PolynomialHash::with_random_base(&text, modulus),
+        ];
+
+        for hash in &hashes {
+            for start in 0..text.len() {
+                for end in start..=text.len().min(start + 20) {
+                    assert_eq!(hash.hash(start..end), hash.hash_of(&text[start..end]));
+                }
+            }
+
+            assert_eq!(hash.hash(0..13), hash.hash(13 * 7..13 * 8));
+            assert_ne!(hash.hash(0..13), hash.hash(1..14));
+        }
+    }
+
+    #[test]
+    fn hash_small_modulus_unit_1() {
+        // 255 and 256 would be 0 mod 257 if only shifted by one
+        for sym in [255u16, 256] {
+            let text = [sym, sym];
+            let hash = PolynomialHash::with_params(&text, 257, 3);
+
+            assert_ne!(hash.hash(0..0), hash.hash(0..1));
+            assert_ne!(hash.hash(0..1), hash.hash(0..2));
+            assert_ne!(hash.hash(0..0), hash.hash(0..2));
+            assert_eq!(hash.hash(0..2), hash.hash_of(&text));
+        }
+    }
+
+    #[test]
+    fn hash_lcp_unit_1() {
+        let text = b"abacabadabacabae";
+
+        for hashes in [DoubleHash::new(text), DoubleHash::with_random_bases(text)] {
+            assert_eq!(hashes.lcp(0..16, 8..16), 7);
+            assert_eq!(hashes.lcp(0..5, 8..16), 5);
+            assert_eq!(hashes.lcp(0..16, 1..16), 0);
+            assert_eq!(hashes.lcp(4..8, 12..15), 3);
+            assert_eq!(hashes.lcp(3..3, 0..16), 0);
+        }
+    }
+
+    #[test]
+    fn rabin_karp_unit_1() {
+        let text = "abccab ёжикabcaab ежи bcaa".repeat(3);
+        let patterns = [
+            "ab",
+            "bca",
+            "ab",
+            "ёж",
+            "жи",
+            "",
+            "abccab ёжикabcaab ежи bcaaa",
+        ];
+        let found = rabin_karp_str(&patterns, &text);
+
+        for (pattern, offsets) in patterns.iter().zip(&found) {
+            assert_eq!(*offsets, naive(pattern, &text), "{}", pattern);
+        }
+
+        assert_eq!(rabin_karp(&[[1u8, 2]], &[1, 2, 1, 2]), [[0, 2]]);
+    }
+}
@@ -1,43 +1,229 @@
+use std::io::{self, ErrorKind, Read};
+
 use crate::string::prefix_function::prefix_function;
 
+static READ_BUFFER_SIZE: usize = 1 << 13;
+
+/// Start of every occurrence of `pattern` in `text`, overlapping ones
+/// included. An empty pattern occurs at every position.\
 /// O(m + n) time\
 /// O(m) space
-pub fn kmp(pattern: &str, text: &str) -> Vec<usize> {
+pub fn kmp<T>(pattern: &[T], text: &[T]) -> Vec<usize>
+where
+    T: Eq,
+{
     let pattern_len = pattern.len();
-    let text_len = text.len();
-
     let mut answer = Vec::new();
 
-    if pattern_len > text_len {
+    if pattern_len == 0 {
+        answer.extend(0..=text.len());
         return answer;
     }
 
     // O(m) space
-    let prefix_array = prefix_function(&(pattern.to_owned() + "#"));
-    let mut curr = prefix_array[pattern_len]; // 0
+    let prefix_array = prefix_function(pattern);
 
-    // prefix_array[prev] <= pattern_len
-    let get_next = |mut prev: usize, index: usize| -> usize {
-        while prev > 0 && text.chars().nth(index) != pattern.chars().nth(prev) {
-            prev = prefix_array[prev - 1];
+    // Length of the longest prefix of the pattern ending at the current position
+    let mut curr = 0;
+
+    for (i, item) in text.iter().enumerate() {
+        curr = next_state(pattern, &prefix_array, curr, item);
+
+        if curr == pattern_len {
+            answer.push(i + 1 - pattern_len);
+            curr = prefix_array[curr - 1];
         }
+    }
+
+    answer
+}
+
+/// `kmp` over the bytes of the strings, returns byte offsets into `text`.
+/// Matches of valid UTF-8 always start on char boundaries,
+/// so `&text[offset..]` starts with `pattern`.\
+/// O(m + n) time\
+/// O(m) space
+pub fn kmp_str(pattern: &str, text: &str) -> Vec<usize> {
+    let mut answer = kmp(pattern.as_bytes(), text.as_bytes());
+
+    // An empty pattern matches between the bytes of a char too
+    if pattern.is_empty() {
+        answer.retain(|&offset| text.is_char_boundary(offset));
+    }
+
+    answer
+}
+
+/// Finds `pattern` in text that arrives piece by piece. The state
+/// is kept between calls, so matches across chunk boundaries are found,
+/// and offsets count every symbol fed since creation or `reset`.
+#[derive(Debug, Clone)]
+pub struct KmpMatcher<T> {
+    pattern: Vec<T>,
+    prefix_array: Vec<usize>,
+    state: usize,
+    position: usize,
+}
 
-        if text.chars().nth(index) == pattern.chars().nth(prev) {
-            prev += 1;
+impl<T> KmpMatcher<T>
+where
+    T: Eq,
+{
+    /// Panics if `pattern` is empty.\
+    /// O(m) time\
+    /// O(m) space
+    pub fn new(pattern: Vec<T>) -> Self {
+        assert!(!pattern.is_empty(), "empty pattern");
+
+        let prefix_array = prefix_function(&pattern);
+
+        Self {
+            pattern,
+            prefix_array,
+            state: 0,
+            position: 0,
         }
+    }
 
-        prev
-    };
+    pub fn pattern(&self) -> &[T] {
+        &self.pattern
+    }
 
-    for i in 0..text_len {
-        curr = get_next(curr, i);
+    /// Number of symbols fed so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
 
-        if curr == pattern_len {
-            answer.push(i + 1 - pattern_len);
+    /// Forgets the text fed so far.
+    pub fn reset(&mut self) {
+        self.state = 0;
+        self.position = 0;
+    }
+
+    /// Feeds one symbol, returns the offset of the match ending with it.\
+    /// O(1) time (amortized)
+    pub fn push(&mut self, item: &T) -> Option<usize> {
+        self.state = next_state(&self.pattern, &self.prefix_array, self.state, item);
+        self.position += 1;
+
+        if self.state == self.pattern.len() {
+            self.state = self.prefix_array[self.state - 1];
+            Some(self.position - self.pattern.len())
+        } else {
+            None
         }
     }
 
-    answer
+    /// Feeds a chunk of text, returns the offsets of the matches ending in it.\
+    /// O(n) time
+    pub fn feed(&mut self, chunk: &[T]) -> Vec<usize> {
+        chunk.iter().filter_map(|item| self.push(item)).collect()
+    }
+
+    /// Offsets of the matches in `text`, found as the iterator is consumed.
+    pub fn matches<I>(&mut self, text: I) -> Matches<'_, T, I::IntoIter>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Matches {
+            matcher: self,
+            text: text.into_iter(),
+        }
+    }
+}
+
+impl KmpMatcher<u8> {
+    /// Byte offsets of the matches in everything `reader` yields,
+    /// found as the iterator is consumed. Read errors are passed on,
+    /// and reading resumes on the next call.
+    pub fn read_matches<R>(&mut self, reader: R) -> ReadMatches<'_, R>
+    where
+        R: Read,
+    {
+        ReadMatches {
+            matcher: self,
+            reader,
+            buffer: vec![0; READ_BUFFER_SIZE],
+            start: 0,
+            end: 0,
+        }
+    }
+}
+
+/// Iterator returned by `KmpMatcher::matches`.
+#[derive(Debug)]
+pub struct Matches<'a, T, I> {
+    matcher: &'a mut KmpMatcher<T>,
+    text: I,
+}
+
+impl<T, I> Iterator for Matches<'_, T, I>
+where
+    T: Eq,
+    I: Iterator<Item = T>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let matcher = &mut *self.matcher;
+
+        self.text.find_map(|item| matcher.push(&item))
+    }
+}
+
+/// Iterator returned by `KmpMatcher::read_matches`.
+#[derive(Debug)]
+pub struct ReadMatches<'a, R> {
+    matcher: &'a mut KmpMatcher<u8>,
+    reader: R,
+    buffer: Vec<u8>,
+    start: usize,
+    end: usize,
+}
+
+impl<R> Iterator for ReadMatches<'_, R>
+where
+    R: Read,
+{
+    type Item = io::Result<usize>;
+
+    fn next(&mut self) -> Option<io::Result<usize>> {
+        loop {
+            while self.start < self.end {
+                let byte = self.buffer[self.start];
+                self.start += 1;
+
+                if let Some(offset) = self.matcher.push(&byte) {
+                    return Some(Ok(offset));
+                }
+            }
+
+            match self.reader.read(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(read) => {
+                    self.start = 0;
+                    self.end = read;
+                }
+                Err(error) if error.kind() == ErrorKind::Interrupted => (),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+fn next_state<T>(pattern: &[T], prefix_array: &[usize], mut state: usize, item: &T) -> usize
+where
+    T: Eq,
+{
+    while state > 0 && *item != pattern[state] {
+        state = prefix_array[state - 1];
+    }
+
+    if *item == pattern[state] {
+        state += 1;
+    }
+
+    state
 }
 
 #[cfg(test)]
@@ -48,6 +234,91 @@ mod tests {
     fn kmp_unit() {
         let pattern = "aba";
         let text = "babac";
-        assert_eq!(kmp(pattern, text), [1]);
+        assert_eq!(kmp_str(pattern, text), [1]);
+    }
+
+    #[test]
+    fn kmp_overlapping_unit() {
+        assert_eq!(kmp(b"aa", b"aaaa"), [0, 1, 2]);
+        assert_eq!(kmp(b"abc", b"ab"), []);
+        assert_eq!(kmp(b"", b"ab"), [0, 1, 2]);
+        assert_eq!(kmp(&[1, 2], &[1, 2, 1, 2]), [0, 2]);
+    }
+
+    #[test]
+    fn kmp_multibyte_unit() {
+        let text = "ёж и ёжик";
+        let offsets = kmp_str("ёж", text);
+
+        assert_eq!(offsets, [0, 8]);
+        assert!(offsets
+            .iter()
+            .all(|&offset| text[offset..].starts_with("ёж")));
+
+        assert_eq!(kmp_str("", "ё"), [0, 2]);
+        assert_eq!(kmp_str("é", "eè"), []);
+    }
+
+    #[test]
+    fn kmp_matcher_chunks_unit() {
+        let text = b"abababcabababab";
+        let expected = kmp(b"abab", text);
+        let mut matcher = KmpMatcher::new(b"abab".to_vec());
+
+        // Every match spans a chunk boundary
+        let mut found = Vec::new();
+
+        for chunk in text.chunks(3) {
+            found.extend(matcher.feed(chunk));
+        }
+
+        assert_eq!(found, expected);
+        assert_eq!(matcher.position(), text.len());
+
+        matcher.reset();
+        let found: Vec<usize> = matcher.matches(text.iter().copied()).collect();
+
+        assert_eq!(found, expected);
+    }
+
+    /// Hands out one byte per read and an interruption before each.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        interrupted: bool,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            self.interrupted = !self.interrupted;
+
+            if self.interrupted {
+                return Err(ErrorKind::Interrupted.into());
+            }
+
+            let read = self.bytes.len().min(buffer.len()).min(1);
+            buffer[..read].copy_from_slice(&self.bytes[..read]);
+            self.bytes = &self.bytes[read..];
+
+            Ok(read)
+        }
+    }
+
+    #[test]
+    fn kmp_matcher_read_unit() {
+        let text = "ёж и ёжик, ежи".repeat(1000);
+        let expected = kmp_str("ёж", &text);
+        let mut matcher = KmpMatcher::new("ёж".as_bytes().to_vec());
+
+        let found: io::Result<Vec<usize>> = matcher.read_matches(text.as_bytes()).collect();
+        assert_eq!(found.unwrap(), expected);
+
+        matcher.reset();
+        let reader = Trickle {
+            bytes: text.as_bytes(),
+            interrupted: false,
+        };
+        let found: io::Result<Vec<usize>> = matcher.read_matches(reader).collect();
+
+        assert_eq!(found.unwrap(), expected);
     }
 }
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Range;
+
+/// Root of the odd palindromes, of length -1 so that adding
+/// a char on both sides gives the char alone.
+static IMAGINARY_ROOT: usize = 0;
+/// Root of the even palindromes, the empty string.
+static EMPTY_ROOT: usize = 1;
+
+/// Radii of the longest palindromes at every center, `(odd, even)`.
+/// `text[i + 1 - odd[i]..i + odd[i]]` is the longest odd palindrome
+/// centered at `i`, `text[i - even[i]..i + even[i]]` the longest even one
+/// centered between `i - 1` and `i`. `odd[i]` and `even[i]` are
+/// also the numbers of palindromes with those centers.\
+/// O(n) time\
+/// O(n) space
+pub fn manacher<T>(text: &[T]) -> (Vec<usize>, Vec<usize>)
+where
+    T: Eq,
+{
+    let len = text.len();
+    let mut odd = vec![0; len];
+    let mut even = vec![0; len];
+
+    // Rightmost palindrome found so far, text[left..right]
+    let (mut left, mut right) = (0, 0);
+
+    for i in 0..len {
+        let mut radius = if i < right {
+            odd[left + right - 1 - i].min(right - i)
+        } else {
+            1
+        };
+
+        while i >= radius && i + radius < len && text[i - radius] == text[i + radius] {
+            radius += 1;
+        }
+
+        odd[i] = radius;
+
+        if i + radius > right {
+            (left, right) = (i + 1 - radius, i + radius);
+        }
+    }
+
+    let (mut left, mut right) = (0, 0);
+
+    for i in 0..len {
+        let mut radius = if i < right {
+            even[left + right - i].min(right - i)
+        } else {
+            0
+        };
+
+        while i > radius && i + radius < len && text[i - radius - 1] == text[i + radius] {
+            radius += 1;
+        }
+
+        even[i] = radius;
+
+        if i + radius > right {
+            (left, right) = (i - radius, i + radius);
+        }
+    }
+
+    (odd, even)
+}
+
+/// Range of the leftmost longest palindromic substring of `text`,
+/// empty only if `text` is.\
+/// O(n) time\
+/// O(n) space
+pub fn longest_palindrome<T>(text: &[T]) -> Range<usize>
+where
+    T: Eq,
+{
+    let (odd, even) = manacher(text);
+
+    let odd_ranges = odd.iter().enumerate().map(|(i, &r)| i + 1 - r..i + r);
+    let even_ranges = even.iter().enumerate().map(|(i, &r)| i - r..i + r);
+
+    odd_ranges
+        .chain(even_ranges)
+        .min_by_key(|range| (range.start as isize - range.end as isize, range.start))
+        .unwrap_or(0..0)
+}
+
+/// `longest_palindrome` of the chars of `text`.\
+/// O(n) time\
+/// O(n) space
+pub fn longest_palindrome_str(text: &str) -> &str {
+    let chars: Vec<char> = text.chars().collect();
+    let range = longest_palindrome(&chars);
+
+    // Byte offset of every char, and of the end
+    let offsets: Vec<usize> = text
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain([text.len()])
+        .collect();
+
+    &text[offsets[range.start]..offsets[range.end]]
+}
+
+/// Number of non-empty palindromic substrings of `text`,
+/// counted once for every position they occur at.\
+/// O(n) time\
+/// O(n) space
+pub fn count_palindromes<T>(text: &[T]) -> usize
+where
+    T: Eq,
+{
+    let (odd, even) = manacher(text);
+
+    odd.iter().sum::<usize>() + even.iter().sum::<usize>()
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    /// -1 for the imaginary root.
+    length: isize,
+    /// Longest proper suffix palindrome.
+    link: usize,
+    next: HashMap<T, usize>,
+    /// End of the first occurrence, inclusive.
+    end: usize,
+    /// Times the node was the longest suffix palindrome.
+    count: usize,
+}
+
+impl<T> Node<T> {
+    fn new(length: isize, link: usize, end: usize) -> Self {
+        Self {
+            length,
+            link,
+            next: HashMap::new(),
+            end,
+            count: 0,
+        }
+    }
+}
+
+/// Palindromic tree of a text that grows one symbol at a time.
+/// Every distinct palindromic substring is a node, its children are
+/// itself with a symbol added on both sides, and its suffix link points
+/// to its longest proper suffix palindrome.
+#[derive(Debug, Clone)]
+pub struct Eertree<T> {
+    text: Vec<T>,
+    nodes: Vec<Node<T>>,
+    /// Longest suffix palindrome of the text.
+    last: usize,
+}
+
+impl<T> Default for Eertree<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Eertree<T>
+where
+    T: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            text: Vec::new(),
+            nodes: vec![
+                Node::new(-1, IMAGINARY_ROOT, 0),
+                Node::new(0, IMAGINARY_ROOT, 0),
+            ],
+            last: EMPTY_ROOT,
+        }
+    }
+
+    /// O(n) time\
+    /// O(n) space
+    pub fn build<I>(text: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut ret = Eertree::new();
+
+        for sym in text {
+            ret.push(sym);
+        }
+
+        ret
+    }
+
+    pub fn text(&self) -> &[T] {
+        &self.text
+    }
+
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Appends `sym`, returns whether a new distinct palindrome appeared.
+    /// At most one can with every symbol.\
+    /// O(1) time (amortized)
+    pub fn push(&mut self, sym: T) -> bool {
+        self.text.push(sym.clone());
+        let end = self.text.len() - 1;
+
+        let parent = self.extendable(self.last, end);
+
+        if let Some(&node) = self.nodes[parent].next.get(&sym) {
+            self.last = node;
+            self.nodes[node].count += 1;
+            return false;
+        }
+
+        let length = self.nodes[parent].length + 2;
+
+        let link = if length == 1 {
+            EMPTY_ROOT
+        } else {
+            let suffix = self.extendable(self.nodes[parent].link, end);
+            self.nodes[suffix].next[&sym]
+        };
+
+        let mut node = Node::new(length, link, end);
+        node.count = 1;
+        self.nodes.push(node);
+
+        let node = self.nodes.len() - 1;
+        self.nodes[parent].next.insert(sym, node);
+        self.last = node;
+
+        true
+    }
+
+    /// Number of distinct non-empty palindromic substrings.\
+    /// O(1) time
+    pub fn distinct_palindromes(&self) -> usize {
+        self.nodes.len() - 2
+    }
+
+    /// Longest palindrome the text ends with.\
+    /// O(1) time
+    pub fn longest_suffix_palindrome(&self) -> &[T] {
+        self.palindrome(self.last)
+    }
+
+    /// Every distinct palindrome with the number of positions it occurs at,
+    /// in the order they first appeared.\
+    /// O(n) time
+    pub fn occurrences(&self) -> Vec<(&[T], usize)> {
+        let mut counts: Vec<usize> = self.nodes.iter().map(|node| node.count).collect();
+
+        // Links point to earlier nodes, so every count is final before it is passed on
+        for node in (2..self.nodes.len()).rev() {
+            counts[self.nodes[node].link] += counts[node];
+        }
+
+        (2..self.nodes.len())
+            .map(|node| (self.palindrome(node), counts[node]))
+            .collect()
+    }
+
+    /// Follows suffix links from `node` to the longest palindrome
+    /// that the symbol at `end` can be added around.
+    fn extendable(&self, mut node: usize, end: usize) -> usize {
+        loop {
+            let length = self.nodes[node].length;
+
+            // The imaginary root always fits, the symbol alone
+            if length == -1 {
+                return node;
+            }
+
+            let length = length as usize;
+
+            if end > length && self.text[end - length - 1] == self.text[end] {
+                return node;
+            }
+
+            node = self.nodes[node].link;
+        }
+    }
+
+    fn palindrome(&self, node: usize) -> &[T] {
+        let node = &self.nodes[node];
+
+        // The roots are empty, and made before any text
+        if node.length <= 0 {
+            return &[];
+        }
+
+        &self.text[node.end + 1 - node.length as usize..=node.end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_palindrome<T: Eq>(text: &[T]) -> bool {
+        text.iter().eq(text.iter().rev())
+    }
+
+    #[test]
+    fn manacher_unit_1() {
+        let text = b"abacabaabbab";
+        let (odd, even) = manacher(text);
+
+        for i in 0..text.len() {
+            let expected_odd = (1..=i + 1)
+                .take_while(|&r| i + r <= text.len() && is_palindrome(&text[i + 1 - r..i + r]))
+                .count();
+            let expected_even = (1..=i)
+                .take_while(|&r| i + r <= text.len() && is_palindrome(&text[i - r..i + r]))
+                .count();
+
+            assert_eq!((odd[i], even[i]), (expected_odd, expected_even), "{}", i);
+        }
+
+        let expected = (0..text.len())
+            .flat_map(|start| (start + 1..=text.len()).map(move |end| start..end))
+            .filter(|range| is_palindrome(&text[range.clone()]))
+            .count();
+
+        assert_eq!(count_palindromes(text), expected);
+        assert_eq!(count_palindromes::<u8>(&[]), 0);
+    }
+
+    #[test]
+    fn longest_palindrome_unit_1() {
+        assert_eq!(longest_palindrome(b"xabacabay"), 1..8);
+        assert_eq!(longest_palindrome(b"abbaxabba"), 0..9);
+        assert_eq!(longest_palindrome(b"xyabbazz"), 2..6);
+        assert_eq!(longest_palindrome(b"abc"), 0..1);
+        assert_eq!(longest_palindrome::<u8>(&[]), 0..0);
+
+        assert_eq!(longest_palindrome_str("ёжикижё!"), "ёжикижё");
+        assert_eq!(longest_palindrome_str("aжжb"), "жж");
+        assert_eq!(longest_palindrome_str(""), "");
+    }
+
+    #[test]
+    fn eertree_unit_1() {
+        let text = "abacabaabbabёжё";
+        let mut tree = Eertree::new();
+        let mut expected: HashMap<Vec<char>, usize> = HashMap::new();
+        let chars: Vec<char> = text.chars().collect();
+
+        for (end, &sym) in chars.iter().enumerate() {
+            let new_palindromes = (0..=end)
+                .filter(|&start| is_palindrome(&chars[start..=end]))
+                .filter(|&start| !expected.contains_key(&chars[start..=end]))
+                .count();
+
+            assert_eq!(tree.push(sym), new_palindromes == 1);
+
+            for start in (0..=end).filter(|&start| is_palindrome(&chars[start..=end])) {
+                *expected.entry(chars[start..=end].to_vec()).or_default() += 1;
+            }
+
+            let longest = (0..=end)
+                .find(|&start| is_palindrome(&chars[start..=end]))
+                .unwrap();
+
+            assert_eq!(tree.longest_suffix_palindrome(), &chars[longest..=end]);
+            assert_eq!(tree.distinct_palindromes(), expected.len());
+        }
+
+        let found: HashMap<Vec<char>, usize> = tree
+            .occurrences()
+            .into_iter()
+            .map(|(palindrome, count)| (palindrome.to_vec(), count))
+            .collect();
+
+        assert_eq!(found, expected);
+        assert_eq!(Eertree::build(b"aaaa".iter()).occurrences()[3].1, 1);
+        assert!(Eertree::<u8>::new().longest_suffix_palindrome().is_empty());
+    }
+}
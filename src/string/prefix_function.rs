@@ -1,16 +1,22 @@
-/// O(n) time
-pub fn prefix_function(string: &str) -> Vec<usize> {
+/// `result[i]` is the length of the longest proper prefix
+/// of `string[..=i]` that is also its suffix.\
+/// O(n) time\
+/// O(n) space
+pub fn prefix_function<T>(string: &[T]) -> Vec<usize>
+where
+    T: Eq,
+{
     let strlen = string.len();
     let mut prefix_array = vec![0; strlen];
 
     for i in 1..strlen {
         let mut prev = prefix_array[i - 1];
 
-        while prev > 0 && string.chars().nth(i) != string.chars().nth(prev) {
+        while prev > 0 && string[i] != string[prev] {
             prev = prefix_array[prev - 1];
         }
 
-        if string.chars().nth(i) == string.chars().nth(prev) {
+        if string[i] == string[prev] {
             prev += 1;
         }
 
@@ -20,6 +26,16 @@ pub fn prefix_function(string: &str) -> Vec<usize> {
     prefix_array
 }
 
+/// `prefix_function` over the chars of `string`:
+/// one value per char, lengths counted in chars.\
+/// O(n) time\
+/// O(n) space
+pub fn prefix_function_str(string: &str) -> Vec<usize> {
+    let chars: Vec<char> = string.chars().collect();
+
+    prefix_function(&chars)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,18 +43,28 @@ mod tests {
     #[test]
     fn prefix_function_unit_1() {
         let string = "aaaaa";
-        assert_eq!(prefix_function(string), [0, 1, 2, 3, 4]);
+        assert_eq!(prefix_function_str(string), [0, 1, 2, 3, 4]);
     }
 
     #[test]
     fn prefix_function_unit_2() {
         let string = "aabaaab";
-        assert_eq!(prefix_function(string), [0, 1, 0, 1, 2, 2, 3]);
+        assert_eq!(prefix_function_str(string), [0, 1, 0, 1, 2, 2, 3]);
     }
 
     #[test]
     fn prefix_function_unit_3() {
         let string = "abcabcd";
-        assert_eq!(prefix_function(string), [0, 0, 0, 1, 2, 3, 0]);
+        assert_eq!(prefix_function_str(string), [0, 0, 0, 1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn prefix_function_multibyte_1() {
+        let string = "жёжё ж";
+        assert_eq!(prefix_function_str(string), [0, 0, 1, 2, 0, 1]);
+        assert_eq!(prefix_function(string.as_bytes()).len(), 11);
+
+        let words = ["to", "be", "or", "not", "to", "be"];
+        assert_eq!(prefix_function(&words), [0, 0, 0, 0, 1, 2]);
     }
 }
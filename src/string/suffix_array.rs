@@ -0,0 +1,509 @@
+use std::mem;
+
+static NO_SUFFIX: usize = usize::MAX;
+
+/// Starts of the suffixes of `string` in lexicographic order,
+/// built by prefix doubling for any ordered alphabet.\
+/// O(n log n) time\
+/// O(n) space
+pub fn prefix_doubling<T>(string: &[T]) -> Vec<usize>
+where
+    T: Ord,
+{
+    let size = string.len();
+    let mut suffix_array: Vec<usize> = (0..size).collect();
+
+    if size == 0 {
+        return suffix_array;
+    }
+
+    suffix_array.sort_by(|&a, &b| string[a].cmp(&string[b]));
+
+    // Ranks of the first `length` symbols of every suffix
+    let mut rank = vec![0; size];
+    let mut next_rank = vec![0; size];
+    let mut second = Vec::with_capacity(size);
+    let mut count = vec![0; size + 1];
+
+    for i in 1..size {
+        let (prev, curr) = (suffix_array[i - 1], suffix_array[i]);
+        rank[curr] = rank[prev] + usize::from(string[prev] != string[curr]);
+    }
+
+    let mut length = 1;
+
+    while length < size && rank[suffix_array[size - 1]] < size - 1 {
+        // Order by the second half first: the suffixes too short
+        // to have one, then the rest as their second halves are ordered
+        second.clear();
+        second.extend(size - length..size);
+        second.extend(
+            suffix_array
+                .iter()
+                .filter(|&&i| i >= length)
+                .map(|&i| i - length),
+        );
+
+        // Then stable counting sort by the first half
+        count.fill(0);
+
+        for &i in &rank {
+            count[i + 1] += 1;
+        }
+
+        for i in 1..count.len() {
+            count[i] += count[i - 1];
+        }
+
+        for &i in &second {
+            suffix_array[count[rank[i]]] = i;
+            count[rank[i]] += 1;
+        }
+
+        let key = |i: usize| (rank[i], rank.get(i + length));
+        next_rank[suffix_array[0]] = 0;
+
+        for i in 1..size {
+            let (prev, curr) = (suffix_array[i - 1], suffix_array[i]);
+            next_rank[curr] = next_rank[prev] + usize::from(key(prev) != key(curr));
+        }
+
+        mem::swap(&mut rank, &mut next_rank);
+        length *= 2;
+    }
+
+    suffix_array
+}
+
+/// Starts of the suffixes of `string` in lexicographic order, built by
+/// induced sorting. Every symbol must be less than `alphabet_size`.\
+/// O(n + k) time, k = alphabet_size\
+/// O(n + k) space
+pub fn sa_is<T>(string: &[T], alphabet_size: usize) -> Vec<usize>
+where
+    T: Copy + Into<usize>,
+{
+    let symbols: Vec<usize> = string
+        .iter()
+        .map(|&symbol| {
+            let symbol = symbol.into();
+            assert!(
+                symbol < alphabet_size,
+                "symbol {} exceeds the alphabet size {}",
+                symbol,
+                alphabet_size
+            );
+            symbol
+        })
+        .collect();
+
+    sa_is_helper(&symbols, alphabet_size.saturating_sub(1))
+}
+
+/// `result[i]` is the length of the longest common prefix of the suffixes
+/// at `suffix_array[i - 1]` and `suffix_array[i]`, 0 for `i = 0` (Kasai).\
+/// O(n) time\
+/// O(n) space
+pub fn lcp_array<T>(string: &[T], suffix_array: &[usize]) -> Vec<usize>
+where
+    T: Eq,
+{
+    let size = string.len();
+    let mut rank = vec![0; size];
+    let mut lcp = vec![0; size];
+
+    for (i, &suffix) in suffix_array.iter().enumerate() {
+        rank[suffix] = i;
+    }
+
+    // The common prefix shrinks by at most one from a suffix to the next
+    let mut common = 0;
+
+    for i in 0..size {
+        if rank[i] == 0 {
+            common = 0;
+            continue;
+        }
+
+        let prev = suffix_array[rank[i] - 1];
+
+        while i + common < size
+            && prev + common < size
+            && string[i + common] == string[prev + common]
+        {
+            common += 1;
+        }
+
+        lcp[rank[i]] = common;
+        common = common.saturating_sub(1);
+    }
+
+    lcp
+}
+
+/// Sorted suffixes of a string with their LCP array, for substring queries.
+#[derive(Debug, Default, Clone)]
+pub struct SuffixArray<T> {
+    string: Vec<T>,
+    suffix_array: Vec<usize>,
+    lcp: Vec<usize>,
+}
+
+impl<T> SuffixArray<T>
+where
+    T: Ord + Clone,
+{
+    /// O(n log n) time\
+    /// O(n) space
+    pub fn new(string: &[T]) -> Self {
+        let suffix_array = prefix_doubling(string);
+
+        Self::from_suffix_array(string.to_vec(), suffix_array)
+    }
+
+    fn from_suffix_array(string: Vec<T>, suffix_array: Vec<usize>) -> Self {
+        let lcp = lcp_array(&string, &suffix_array);
+
+        Self {
+            string,
+            suffix_array,
+            lcp,
+        }
+    }
+
+    pub fn suffix_array(&self) -> &[usize] {
+        &self.suffix_array
+    }
+
+    pub fn lcp_array(&self) -> &[usize] {
+        &self.lcp
+    }
+
+    /// Number of occurrences of `pattern`, overlapping ones included.\
+    /// O(m log n) time
+    pub fn count(&self, pattern: &[T]) -> usize {
+        let (start, end) = self.find(pattern);
+
+        end - start
+    }
+
+    /// Starts of the occurrences of `pattern` in increasing order.\
+    /// O(m log n + k log k) time, k = number of occurrences
+    pub fn locate(&self, pattern: &[T]) -> Vec<usize> {
+        let (start, end) = self.find(pattern);
+        let mut positions = self.suffix_array[start..end].to_vec();
+        positions.sort_unstable();
+
+        positions
+    }
+
+    /// One of the longest substrings that occur at least twice,
+    /// `None` if no symbol repeats.\
+    /// O(n) time
+    pub fn longest_repeated_substring(&self) -> Option<&[T]> {
+        let (index, &length) = self
+            .lcp
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, &length)| length)?;
+
+        if length == 0 {
+            return None;
+        }
+
+        let start = self.suffix_array[index];
+
+        Some(&self.string[start..start + length])
+    }
+
+    /// Number of distinct non-empty substrings.\
+    /// O(n) time
+    pub fn distinct_substrings(&self) -> usize {
+        let size = self.string.len();
+
+        size * (size + 1) / 2 - self.lcp.iter().sum::<usize>()
+    }
+
+    /// Range of the suffix array starting with `pattern`.
+    fn find(&self, pattern: &[T]) -> (usize, usize) {
+        let head = |suffix: usize| {
+            let rest = &self.string[suffix..];
+            &rest[..rest.len().min(pattern.len())]
+        };
+
+        let start = self
+            .suffix_array
+            .partition_point(|&suffix| head(suffix) < pattern);
+        let end = self
+            .suffix_array
+            .partition_point(|&suffix| head(suffix) <= pattern);
+
+        (start, end)
+    }
+}
+
+impl SuffixArray<u8> {
+    /// Suffix array of the bytes of `string`,
+    /// positions and lengths are in bytes.\
+    /// O(n) time\
+    /// O(n) space
+    pub fn build(string: &str) -> Self {
+        let suffix_array = sa_is(string.as_bytes(), 1 << u8::BITS);
+
+        Self::from_suffix_array(string.as_bytes().to_vec(), suffix_array)
+    }
+}
+
+/// SA-IS on symbols up to `upper`. Suffixes are S-type if less than the
+/// next suffix, L-type otherwise, and LMS if S-type after an L-type one.
+/// Sorting the LMS substrings and inducing from them orders the rest,
+/// and the LMS substrings are sorted by recursing on their ranks.
+fn sa_is_helper(string: &[usize], upper: usize) -> Vec<usize> {
+    let size = string.len();
+
+    match size {
+        0 => return Vec::new(),
+        1 => return vec![0],
+        2 => {
+            return if string[0] < string[1] {
+                vec![0, 1]
+            } else {
+                vec![1, 0]
+            }
+        }
+        _ => (),
+    }
+
+    let mut is_s = vec![false; size];
+
+    for i in (0..size - 1).rev() {
+        is_s[i] = if string[i] == string[i + 1] {
+            is_s[i + 1]
+        } else {
+            string[i] < string[i + 1]
+        };
+    }
+
+    // Starts of the S-type and L-type parts of every symbol's bucket
+    let mut starts_l = vec![0; upper + 2];
+    let mut starts_s = vec![0; upper + 1];
+
+    for i in 0..size {
+        if is_s[i] {
+            starts_l[string[i] + 1] += 1;
+        } else {
+            starts_s[string[i]] += 1;
+        }
+    }
+
+    for symbol in 0..=upper {
+        starts_s[symbol] += starts_l[symbol];
+        starts_l[symbol + 1] += starts_s[symbol];
+    }
+
+    let mut lms_index = vec![NO_SUFFIX; size];
+    let lms: Vec<usize> = (1..size).filter(|&i| !is_s[i - 1] && is_s[i]).collect();
+
+    for (index, &suffix) in lms.iter().enumerate() {
+        lms_index[suffix] = index;
+    }
+
+    let mut suffix_array = vec![NO_SUFFIX; size];
+    let buckets = Buckets {
+        string,
+        is_s: &is_s,
+        starts_l: &starts_l[..=upper],
+        starts_s: &starts_s,
+    };
+    buckets.induce(&mut suffix_array, &lms);
+
+    if lms.is_empty() {
+        return suffix_array;
+    }
+
+    let mut sorted_lms: Vec<usize> = suffix_array
+        .iter()
+        .copied()
+        .filter(|&suffix| lms_index[suffix] != NO_SUFFIX)
+        .collect();
+
+    // Rank the LMS substrings, equal ones get the same rank
+    let end_of = |suffix: usize| lms.get(lms_index[suffix] + 1).copied().unwrap_or(size);
+    let mut ranks = vec![0; lms.len()];
+    let mut rank = 0;
+
+    for pair in sorted_lms.windows(2) {
+        let (prev, curr) = (pair[0], pair[1]);
+        let (prev_end, curr_end) = (end_of(prev), end_of(curr));
+
+        let same = prev_end - prev == curr_end - curr
+            && prev_end < size
+            && curr_end < size
+            && string[prev..=prev_end] == string[curr..=curr_end];
+
+        if !same {
+            rank += 1;
+        }
+
+        ranks[lms_index[curr]] = rank;
+    }
+
+    let ranks_order = sa_is_helper(&ranks, rank);
+
+    for (sorted, &index) in sorted_lms.iter_mut().zip(&ranks_order) {
+        *sorted = lms[index];
+    }
+
+    buckets.induce(&mut suffix_array, &sorted_lms);
+
+    suffix_array
+}
+
+struct Buckets<'a> {
+    string: &'a [usize],
+    is_s: &'a [bool],
+    starts_l: &'a [usize],
+    starts_s: &'a [usize],
+}
+
+impl Buckets<'_> {
+    /// Puts the LMS suffixes at the ends of their buckets in the given
+    /// order, then induces the L-type suffixes left to right and the
+    /// S-type suffixes right to left.
+    fn induce(&self, suffix_array: &mut [usize], lms: &[usize]) {
+        let (string, is_s) = (self.string, self.is_s);
+        let size = string.len();
+        suffix_array.fill(NO_SUFFIX);
+
+        let mut next = self.starts_s.to_vec();
+
+        for &suffix in lms {
+            suffix_array[next[string[suffix]]] = suffix;
+            next[string[suffix]] += 1;
+        }
+
+        next.copy_from_slice(self.starts_l);
+        suffix_array[next[string[size - 1]]] = size - 1;
+        next[string[size - 1]] += 1;
+
+        for i in 0..size {
+            let suffix = suffix_array[i];
+
+            if suffix != NO_SUFFIX && suffix > 0 && !is_s[suffix - 1] {
+                suffix_array[next[string[suffix - 1]]] = suffix - 1;
+                next[string[suffix - 1]] += 1;
+            }
+        }
+
+        // Ends of the buckets, the start of the next bucket's L-type part
+        next.copy_from_slice(self.starts_l);
+        next.rotate_left(1);
+        *next.last_mut().unwrap() = size;
+
+        for i in (0..size).rev() {
+            let suffix = suffix_array[i];
+
+            if suffix != NO_SUFFIX && suffix > 0 && is_s[suffix - 1] {
+                next[string[suffix - 1]] -= 1;
+                suffix_array[next[string[suffix - 1]]] = suffix - 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    fn naive(string: &[u8]) -> Vec<usize> {
+        let mut suffix_array: Vec<usize> = (0..string.len()).collect();
+        suffix_array.sort_by_key(|&suffix| &string[suffix..]);
+
+        suffix_array
+    }
+
+    /// Strings over small alphabets, with many repeats.
+    fn strings() -> Vec<Vec<u8>> {
+        let mut random = 1u32;
+        let mut strings = vec![Vec::new(), b"a".to_vec(), b"aaaa".to_vec(), b"ba".to_vec()];
+
+        for size in 1..200 {
+            let alphabet = size % 4 + 1;
+
+            strings.push(
+                (0..size)
+                    .map(|_| {
+                        random ^= random << 13;
+                        random ^= random >> 17;
+                        random ^= random << 5;
+                        b'a' + (random % alphabet) as u8
+                    })
+                    .collect(),
+            );
+        }
+
+        strings
+    }
+
+    #[test]
+    fn suffix_array_unit_1() {
+        for string in strings() {
+            let expected = naive(&string);
+
+            assert_eq!(prefix_doubling(&string), expected, "{:?}", string);
+            assert_eq!(sa_is(&string, 256), expected, "{:?}", string);
+        }
+
+        assert_eq!(prefix_doubling(b"banana"), [5, 3, 1, 0, 4, 2]);
+        assert_eq!(
+            prefix_doubling(&["to", "be", "or", "not", "to", "be"]),
+            [5, 1, 3, 2, 4, 0]
+        );
+    }
+
+    #[test]
+    fn lcp_array_unit_1() {
+        for string in strings() {
+            let suffix_array = sa_is(&string, 256);
+            let lcp = lcp_array(&string, &suffix_array);
+
+            for i in 1..string.len() {
+                let (a, b) = (&string[suffix_array[i - 1]..], &string[suffix_array[i]..]);
+                let common = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+
+                assert_eq!(lcp[i], common);
+            }
+        }
+    }
+
+    #[test]
+    fn suffix_array_queries_unit_1() {
+        let suffix_array = SuffixArray::build("mississippi");
+
+        assert_eq!(suffix_array.count(b"ss"), 2);
+        assert_eq!(suffix_array.count(b"i"), 4);
+        assert_eq!(suffix_array.count(b"sp"), 0);
+        assert_eq!(suffix_array.count(b""), 11);
+        assert_eq!(suffix_array.locate(b"issi"), [1, 4]);
+        assert_eq!(
+            suffix_array.longest_repeated_substring(),
+            Some(&b"issi"[..])
+        );
+
+        for string in strings() {
+            let string = &string[..];
+            let suffix_array = SuffixArray::new(string);
+            let distinct: HashSet<&[u8]> = (0..string.len())
+                .flat_map(|start| (start + 1..=string.len()).map(move |end| &string[start..end]))
+                .collect();
+
+            assert_eq!(suffix_array.distinct_substrings(), distinct.len());
+        }
+
+        let multibyte = SuffixArray::build("ёжик ёж");
+        assert_eq!(multibyte.locate("ёж".as_bytes()), [0, 9]);
+        assert_eq!(SuffixArray::new(b"abc").longest_repeated_substring(), None);
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::OnceLock;
 
 static ROOT: usize = 0;
 static NO_LINK: usize = usize::MAX;
@@ -9,12 +10,28 @@ struct State {
     link: usize,
     next: HashMap<char, usize>,
     is_terminal: bool,
+    is_clone: bool,
+    /// Char index where the first occurrence of the state's strings ends.
+    first_end: usize,
+}
+
+/// Per state values the queries need, computed on first use.
+#[derive(Debug, Clone)]
+struct Statistics {
+    /// Number of end positions of the state's strings.
+    occurrences: Vec<usize>,
+    /// Number of distinct strings read from the state, the empty one included.
+    paths: Vec<usize>,
+    /// Transitions in lexicographic order.
+    sorted_next: Vec<Vec<(char, usize)>>,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct SuffixAutomaton {
     last: usize,
     states: Vec<State>,
+    /// Reset whenever the text changes.
+    statistics: OnceLock<Statistics>,
 }
 
 impl SuffixAutomaton {
@@ -28,7 +45,11 @@ impl SuffixAutomaton {
         let mut states = vec![State::default()];
         states[ROOT].link = NO_LINK;
 
-        Self { last: ROOT, states }
+        Self {
+            last: ROOT,
+            states,
+            statistics: OnceLock::new(),
+        }
     }
 
     /// O(n) time
@@ -45,31 +66,239 @@ impl SuffixAutomaton {
         self.states.shrink_to_fit();
         self.last = self.new_state();
         self.states[ROOT].link = NO_LINK;
+        self.statistics = OnceLock::new();
     }
 
     /// O(n) time
     pub fn contains(&self, string: &str) -> bool {
-        let mut cur = ROOT;
+        self.find(string).is_some()
+    }
+
+    /// Appends `string` to the text, so substrings can span the strings
+    /// added before. See `GeneralizedSuffixAutomaton` to keep them apart.\
+    /// O(n) time\
+    /// O(n) space
+    pub fn add_string(&mut self, string: &str) {
+        self.statistics = OnceLock::new();
+        self.unmark_terminal();
 
         for sym in string.chars() {
-            if !self.states[cur].next.contains_key(&sym) {
-                return false;
+            self.add_char(sym);
+        }
+
+        self.mark_terminal();
+    }
+
+    /// Whether `string` is a suffix of the text, the empty string included.\
+    /// O(m) time
+    pub fn is_suffix(&self, string: &str) -> bool {
+        self.find(string)
+            .is_some_and(|state| self.states[state].is_terminal)
+    }
+
+    /// Number of distinct non-empty substrings.\
+    /// O(n) time
+    pub fn distinct_substrings(&self) -> usize {
+        self.states
+            .iter()
+            .skip(1)
+            .map(|state| state.length - self.states[state.link].length)
+            .sum()
+    }
+
+    /// Total length in chars of the distinct non-empty substrings.\
+    /// O(n) time
+    pub fn distinct_substrings_length(&self) -> usize {
+        // Every state holds the substrings with lengths
+        // from the length of its link, exclusive, up to its own
+        let triangle = |length: usize| length * (length + 1) / 2;
+
+        self.states
+            .iter()
+            .skip(1)
+            .map(|state| triangle(state.length) - triangle(self.states[state.link].length))
+            .sum()
+    }
+
+    /// Number of occurrences of `pattern`, overlapping ones included.
+    /// An empty pattern occurs at every position, the end included.\
+    /// O(m) time, O(n) more for the first query after a change
+    pub fn count_occurrences(&self, pattern: &str) -> usize {
+        let state = match self.find(pattern) {
+            Some(state) => state,
+            None => return 0,
+        };
+
+        if state == ROOT {
+            return self.states[self.last].length + 1;
+        }
+
+        self.statistics().occurrences[state]
+    }
+
+    /// Char index of the first occurrence of `pattern`.\
+    /// O(m) time
+    pub fn first_occurrence(&self, pattern: &str) -> Option<usize> {
+        let state = self.find(pattern)?;
+
+        if state == ROOT {
+            return Some(0);
+        }
+
+        Some(self.states[state].first_end + 1 - pattern.chars().count())
+    }
+
+    /// The `index`-th distinct non-empty substring in lexicographic order,
+    /// from 0, or `None` if there are not that many.\
+    /// O(l * k) time, l = length of the answer, k = alphabet size,
+    /// O(n log k) more for the first query after a change
+    pub fn nth_substring(&self, mut index: usize) -> Option<String> {
+        let statistics = self.statistics();
+        let paths = &statistics.paths;
+
+        let mut substring = String::new();
+        let mut cur = ROOT;
+
+        'walk: loop {
+            for &(sym, next) in &statistics.sorted_next[cur] {
+                if index < paths[next] {
+                    substring.push(sym);
+
+                    if index == 0 {
+                        return Some(substring);
+                    }
+
+                    index -= 1;
+                    cur = next;
+                    continue 'walk;
+                }
+
+                index -= paths[next];
             }
 
-            cur = *self.states[cur].next.get(&sym).unwrap();
+            return None;
         }
+    }
+
+    /// Longest substring of `other` that is also a substring of the text.\
+    /// O(m) time
+    pub fn longest_common_substring<'a>(&self, other: &'a str) -> &'a str {
+        let mut cur = ROOT;
+        let mut length = 0;
+        let (mut best_length, mut best_end) = (0, 0);
 
-        true
+        for (index, sym) in other.chars().enumerate() {
+            while cur != ROOT && !self.states[cur].next.contains_key(&sym) {
+                cur = self.states[cur].link;
+                length = self.states[cur].length;
+            }
+
+            if let Some(&next) = self.states[cur].next.get(&sym) {
+                cur = next;
+                length += 1;
+            }
+
+            if length > best_length {
+                best_length = length;
+                best_end = index + 1;
+            }
+        }
+
+        // Byte offsets of the chars, and of the end
+        let offsets: Vec<usize> = other
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(Some(other.len()))
+            .collect();
+
+        &other[offsets[best_end - best_length]..offsets[best_end]]
     }
 
-    /// O(n) time\
-    /// O(n) space
-    pub fn add_string(&mut self, string: &str) {
+    fn statistics(&self) -> &Statistics {
+        self.statistics.get_or_init(|| {
+            let order = self.by_length();
+
+            // Every prefix of the text ends in its own non-cloned state, and
+            // the end positions of a state are those of the states linking to it
+            let mut occurrences: Vec<usize> = self
+                .states
+                .iter()
+                .map(|state| usize::from(!state.is_clone))
+                .collect();
+
+            for &cur in order.iter().rev().filter(|&&cur| cur != ROOT) {
+                occurrences[self.states[cur].link] += occurrences[cur];
+            }
+
+            let sorted_next: Vec<Vec<(char, usize)>> = self
+                .states
+                .iter()
+                .map(|state| {
+                    let mut next: Vec<(char, usize)> =
+                        state.next.iter().map(|(&sym, &next)| (sym, next)).collect();
+                    next.sort_unstable();
+                    next
+                })
+                .collect();
+
+            // Transitions lead to longer states, so those are done first
+            let mut paths = vec![1usize; self.states.len()];
+
+            for &cur in order.iter().rev() {
+                paths[cur] += sorted_next[cur]
+                    .iter()
+                    .map(|&(_, next)| paths[next])
+                    .sum::<usize>();
+            }
+
+            Statistics {
+                occurrences,
+                paths,
+                sorted_next,
+            }
+        })
+    }
+
+    /// State reached by reading `string` from the root.
+    fn find(&self, string: &str) -> Option<usize> {
+        let mut cur = ROOT;
+
         for sym in string.chars() {
-            self.add_char(sym);
+            cur = *self.states[cur].next.get(&sym)?;
         }
 
-        self.mark_terminal();
+        Some(cur)
+    }
+
+    /// States in increasing order of length, by counting sort.
+    fn by_length(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.states[self.last].length + 2];
+
+        for state in &self.states {
+            counts[state.length + 1] += 1;
+        }
+
+        for length in 1..counts.len() {
+            counts[length] += counts[length - 1];
+        }
+
+        let mut order = vec![0; self.states.len()];
+
+        for (index, state) in self.states.iter().enumerate() {
+            order[counts[state.length]] = index;
+            counts[state.length] += 1;
+        }
+
+        order
+    }
+
+    fn unmark_terminal(&mut self) {
+        let mut prev = self.last;
+
+        while prev != NO_LINK {
+            self.states[prev].is_terminal = false;
+            prev = self.states[prev].link;
+        }
     }
 
     fn mark_terminal(&mut self) {
@@ -85,6 +314,7 @@ impl SuffixAutomaton {
     fn add_char(&mut self, sym: char) {
         let cur_state = self.new_state();
         self.states[cur_state].length = self.states[self.last].length + 1;
+        self.states[cur_state].first_end = self.states[self.last].length;
 
         let mut prev = self.last;
 
@@ -105,6 +335,8 @@ impl SuffixAutomaton {
                 self.states[cloned].length = self.states[prev].length + 1;
                 self.states[cloned].next = self.states[next].next.clone();
                 self.states[cloned].link = self.states[next].link;
+                self.states[cloned].is_clone = true;
+                self.states[cloned].first_end = self.states[next].first_end;
 
                 while prev != NO_LINK && self.states[prev].next[&sym] == next {
                     *self.states[prev].next.get_mut(&sym).unwrap() = cloned;
@@ -133,9 +365,97 @@ mod tests {
             sa.add_string(string);
         }
 
-        assert_eq!(sa.contains("the"), true);
-        assert_eq!(sa.contains("these"), false);
-        assert_eq!(sa.contains("their"), true);
-        assert_eq!(sa.contains("thaw"), false);
+        assert!(sa.contains("the"));
+        assert!(!sa.contains("these"));
+        assert!(sa.contains("their"));
+        assert!(!sa.contains("thaw"));
+    }
+
+    #[test]
+    fn suffix_automaton_counts_unit_1() {
+        let string = "abcbcbabcc";
+        let sa = SuffixAutomaton::build(string);
+
+        let mut distinct = std::collections::HashSet::new();
+
+        for start in 0..string.len() {
+            for end in start + 1..=string.len() {
+                distinct.insert(&string[start..end]);
+            }
+        }
+
+        assert_eq!(sa.distinct_substrings(), distinct.len());
+        assert_eq!(
+            sa.distinct_substrings_length(),
+            distinct
+                .iter()
+                .map(|substring| substring.len())
+                .sum::<usize>()
+        );
+
+        for &pattern in &distinct {
+            let expected = (0..string.len())
+                .filter(|&start| string[start..].starts_with(pattern))
+                .count();
+
+            assert_eq!(sa.count_occurrences(pattern), expected);
+            assert_eq!(sa.first_occurrence(pattern), string.find(pattern));
+        }
+
+        assert_eq!(sa.count_occurrences("cc"), 1);
+        assert_eq!(sa.count_occurrences("ca"), 0);
+        assert_eq!(sa.count_occurrences(""), 11);
+        assert_eq!(sa.first_occurrence("bca"), None);
+
+        // The counts are computed again after the text changes
+        let mut sa = sa;
+        sa.add_string("abc");
+
+        assert_eq!(sa.count_occurrences("abc"), 3);
+        assert_eq!(sa.count_occurrences("ca"), 1);
+        assert_eq!(sa.nth_substring(0).as_deref(), Some("a"));
+
+        sa.clear();
+        assert_eq!(sa.count_occurrences("a"), 0);
+    }
+
+    #[test]
+    fn suffix_automaton_nth_substring_unit_1() {
+        let string = "ёжабаж";
+        let sa = SuffixAutomaton::build(string);
+        let chars: Vec<char> = string.chars().collect();
+
+        let mut distinct: Vec<String> = (0..chars.len())
+            .flat_map(|start| (start + 1..=chars.len()).map(move |end| (start, end)))
+            .map(|(start, end)| chars[start..end].iter().collect())
+            .collect();
+        distinct.sort();
+        distinct.dedup();
+
+        for (index, substring) in distinct.iter().enumerate() {
+            assert_eq!(sa.nth_substring(index).as_ref(), Some(substring));
+        }
+
+        assert_eq!(sa.nth_substring(distinct.len()), None);
+        assert_eq!(sa.first_occurrence("баж"), Some(3));
+    }
+
+    #[test]
+    fn suffix_automaton_lcs_unit_1() {
+        let sa = SuffixAutomaton::build("xabxac ёжик");
+
+        assert_eq!(sa.longest_common_substring("abcabxabcd"), "abxa");
+        assert_eq!(sa.longest_common_substring("ежик"), "жик");
+        assert_eq!(sa.longest_common_substring("zzz"), "");
+
+        assert!(sa.is_suffix("ёжик"));
+        assert!(sa.is_suffix(""));
+        assert!(!sa.is_suffix("ёжи"));
+
+        let mut sa = SuffixAutomaton::build("the");
+        sa.add_string("a");
+
+        assert!(!sa.is_suffix("he"));
+        assert!(sa.is_suffix("a"));
     }
 }
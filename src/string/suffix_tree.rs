@@ -0,0 +1,531 @@
+use std::collections::BTreeMap;
+
+static ROOT: usize = 0;
+static OPEN_END: usize = usize::MAX;
+
+/// Symbol of the documents joined together, each one followed by its own
+/// terminator. Terminators order before every other symbol.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Symbol<T> {
+    Terminator(usize),
+    Char(T),
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    /// Edge from the parent, as positions in the joined documents.
+    start: usize,
+    end: usize,
+    parent: usize,
+    link: usize,
+    /// Start of one occurrence of the path from the root.
+    suffix: usize,
+    /// Length of the path from the root, for internal nodes.
+    depth: usize,
+    is_leaf: bool,
+    children: BTreeMap<Symbol<T>, usize>,
+}
+
+/// Generalized suffix tree of one or more documents, built online with
+/// Ukkonen's algorithm. Every document gets a unique terminator, so each
+/// of its suffixes ends at a leaf. Terminators are never part of labels,
+/// depths or matches.
+#[derive(Debug, Clone)]
+pub struct SuffixTree<T> {
+    nodes: Vec<Node<T>>,
+    documents: Vec<Vec<T>>,
+    /// Position of every document in the joined documents.
+    starts: Vec<usize>,
+    /// Document of every position in the joined documents.
+    document_of: Vec<usize>,
+    active_node: usize,
+    active_edge: usize,
+    active_length: usize,
+    remainder: usize,
+}
+
+impl<T> Default for SuffixTree<T>
+where
+    T: Ord + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SuffixTree<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        let root = Node {
+            start: 0,
+            end: 0,
+            parent: ROOT,
+            link: ROOT,
+            suffix: 0,
+            depth: 0,
+            is_leaf: false,
+            children: BTreeMap::new(),
+        };
+
+        Self {
+            nodes: vec![root],
+            documents: Vec::new(),
+            starts: Vec::new(),
+            document_of: Vec::new(),
+            active_node: ROOT,
+            active_edge: 0,
+            active_length: 0,
+            remainder: 0,
+        }
+    }
+
+    /// O(n log k) time, k = alphabet size\
+    /// O(n) space
+    pub fn build(string: &[T]) -> Self {
+        let mut ret = SuffixTree::new();
+        ret.add_document(string);
+
+        ret
+    }
+
+    /// Adds the suffixes of `document` and returns its index.\
+    /// O(m log k) time, k = alphabet size
+    pub fn add_document(&mut self, document: &[T]) -> usize {
+        let index = self.documents.len();
+        let start = self.document_of.len();
+        let first_node = self.nodes.len();
+
+        self.documents.push(document.to_vec());
+        self.starts.push(start);
+        self.document_of
+            .extend(std::iter::repeat(index).take(document.len() + 1));
+
+        for position in start..self.document_of.len() {
+            self.extend(position);
+        }
+
+        // The unique terminator made every suffix a leaf, which
+        // must not grow with the documents added later
+        for node in &mut self.nodes[first_node..] {
+            if node.end == OPEN_END {
+                node.end = self.document_of.len();
+            }
+        }
+
+        index
+    }
+
+    pub fn document_count(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn document(&self, index: usize) -> &[T] {
+        &self.documents[index]
+    }
+
+    pub fn root(&self) -> usize {
+        ROOT
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_leaf(&self, node: usize) -> bool {
+        self.nodes[node].is_leaf
+    }
+
+    /// `None` for the root.
+    pub fn parent(&self, node: usize) -> Option<usize> {
+        (node != ROOT).then(|| self.nodes[node].parent)
+    }
+
+    /// Children in lexicographic order of their labels,
+    /// those reached by a terminator alone first.
+    pub fn children(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.nodes[node].children.values().copied()
+    }
+
+    /// Every leaf, one per suffix of every document.
+    pub fn leaves(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(|&node| self.nodes[node].is_leaf)
+    }
+
+    /// Every node with children other than the root.
+    pub fn internal_nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        (1..self.nodes.len()).filter(|&node| !self.nodes[node].is_leaf)
+    }
+
+    /// Label of the edge from the parent, a slice of one document.
+    pub fn label(&self, node: usize) -> &[T] {
+        if node == ROOT {
+            return &[];
+        }
+
+        let Node { start, end, .. } = self.nodes[node];
+        let document = self.document_of[start];
+        let offset = start - self.starts[document];
+        let length = end.min(self.terminator(document)).saturating_sub(start);
+
+        &self.documents[document][offset..offset + length]
+    }
+
+    /// Length of the path from the root.
+    pub fn string_depth(&self, node: usize) -> usize {
+        let node = &self.nodes[node];
+
+        if node.is_leaf {
+            self.terminator(self.document_of[node.suffix]) - node.suffix
+        } else {
+            node.depth
+        }
+    }
+
+    /// Labels from the root to `node` joined, a slice of one document.
+    pub fn path_label(&self, node: usize) -> &[T] {
+        if node == ROOT {
+            return &[];
+        }
+
+        let (document, offset) = self.position(self.nodes[node].suffix);
+
+        &self.documents[document][offset..offset + self.string_depth(node)]
+    }
+
+    /// Document and start of the suffix ending at `leaf`, `None` for other nodes.
+    pub fn suffix(&self, leaf: usize) -> Option<(usize, usize)> {
+        let node = &self.nodes[leaf];
+
+        node.is_leaf.then(|| self.position(node.suffix))
+    }
+
+    /// Whether `pattern` is a substring of one of the documents.\
+    /// O(m log k) time
+    pub fn contains(&self, pattern: &[T]) -> bool {
+        self.locus(pattern).is_some()
+    }
+
+    /// Document and start of every occurrence of `pattern`, in no particular order.
+    /// An empty pattern occurs at every position, the ends of the documents included.\
+    /// O(m log k + occ) time
+    pub fn occurrences(&self, pattern: &[T]) -> Vec<(usize, usize)> {
+        let mut occurrences = Vec::new();
+        let mut stack: Vec<usize> = self.locus(pattern).into_iter().collect();
+
+        while let Some(node) = stack.pop() {
+            match self.suffix(node) {
+                Some(occurrence) => occurrences.push(occurrence),
+                None => stack.extend(self.children(node)),
+            }
+        }
+
+        occurrences
+    }
+
+    /// Longest substring occurring in at least `count` of the documents,
+    /// `None` if there is no such non-empty substring.\
+    /// O(n * d / 64) time, d = number of documents
+    pub fn longest_common_substring(&self, count: usize) -> Option<&[T]> {
+        let words = (self.documents.len() + 63) / 64;
+        let mut documents = vec![0u64; self.nodes.len() * words];
+
+        // Children come before their parents in reverse preorder
+        let mut preorder = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![ROOT];
+
+        while let Some(node) = stack.pop() {
+            preorder.push(node);
+            stack.extend(self.children(node));
+        }
+
+        let mut best: Option<usize> = None;
+
+        for &node in preorder.iter().rev() {
+            if let Some((document, _)) = self.suffix(node) {
+                documents[node * words + document / 64] |= 1 << (document % 64);
+            }
+
+            let mask = &documents[node * words..(node + 1) * words];
+            let found: u32 = mask.iter().map(|word| word.count_ones()).sum();
+
+            if found as usize >= count
+                && self.string_depth(node) > best.map_or(0, |best| self.string_depth(best))
+            {
+                best = Some(node);
+            }
+
+            if node != ROOT {
+                let parent = self.nodes[node].parent;
+
+                for word in 0..words {
+                    documents[parent * words + word] |= documents[node * words + word];
+                }
+            }
+        }
+
+        best.map(|node| self.path_label(node))
+    }
+
+    /// Node whose path starts with `pattern` and whose parent's path is shorter.
+    fn locus(&self, pattern: &[T]) -> Option<usize> {
+        let mut node = ROOT;
+        let mut matched = 0;
+
+        while matched < pattern.len() {
+            node = *self.nodes[node]
+                .children
+                .get(&Symbol::Char(pattern[matched].clone()))?;
+
+            let label = self.label(node);
+            let length = label.len().min(pattern.len() - matched);
+
+            if label[..length] != pattern[matched..matched + length] {
+                return None;
+            }
+
+            matched += length;
+        }
+
+        Some(node)
+    }
+
+    fn terminator(&self, document: usize) -> usize {
+        self.starts[document] + self.documents[document].len()
+    }
+
+    fn position(&self, position: usize) -> (usize, usize) {
+        let document = self.document_of[position];
+
+        (document, position - self.starts[document])
+    }
+
+    fn symbol(&self, position: usize) -> Symbol<T> {
+        let (document, offset) = self.position(position);
+
+        match self.documents[document].get(offset) {
+            Some(item) => Symbol::Char(item.clone()),
+            None => Symbol::Terminator(document),
+        }
+    }
+
+    fn edge_length(&self, node: usize, position: usize) -> usize {
+        self.nodes[node].end.min(position + 1) - self.nodes[node].start
+    }
+
+    fn new_node(&mut self, start: usize, end: usize, parent: usize, suffix: usize) -> usize {
+        self.nodes.push(Node {
+            start,
+            end,
+            parent,
+            link: ROOT,
+            suffix,
+            depth: 0,
+            is_leaf: end == OPEN_END,
+            children: BTreeMap::new(),
+        });
+
+        self.nodes.len() - 1
+    }
+
+    /// Ukkonen's step: adds the symbol at `position` to every suffix
+    /// that is not yet explicit. `active_node`, `active_edge` and
+    /// `active_length` point at the longest suffix already in the tree.
+    fn extend(&mut self, position: usize) {
+        let symbol = self.symbol(position);
+        let mut last_internal: Option<usize> = None;
+        self.remainder += 1;
+
+        while self.remainder > 0 {
+            if self.active_length == 0 {
+                self.active_edge = position;
+            }
+
+            let suffix = position + 1 - self.remainder;
+            let edge_symbol = self.symbol(self.active_edge);
+
+            match self.nodes[self.active_node]
+                .children
+                .get(&edge_symbol)
+                .copied()
+            {
+                None => {
+                    let leaf = self.new_node(position, OPEN_END, self.active_node, suffix);
+                    self.nodes[self.active_node]
+                        .children
+                        .insert(symbol.clone(), leaf);
+
+                    if let Some(internal) = last_internal.take() {
+                        self.nodes[internal].link = self.active_node;
+                    }
+                }
+                Some(next) => {
+                    // Skip over whole edges
+                    let edge_length = self.edge_length(next, position);
+
+                    if self.active_length >= edge_length {
+                        self.active_edge += edge_length;
+                        self.active_length -= edge_length;
+                        self.active_node = next;
+                        continue;
+                    }
+
+                    // Already in the tree, and so are all the shorter suffixes
+                    if self.symbol(self.nodes[next].start + self.active_length) == symbol {
+                        if let Some(internal) = last_internal.take() {
+                            if self.active_node != ROOT {
+                                self.nodes[internal].link = self.active_node;
+                            }
+                        }
+
+                        self.active_length += 1;
+                        break;
+                    }
+
+                    let start = self.nodes[next].start;
+                    let split =
+                        self.new_node(start, start + self.active_length, self.active_node, suffix);
+                    self.nodes[split].depth =
+                        self.nodes[self.active_node].depth + self.active_length;
+                    self.nodes[self.active_node]
+                        .children
+                        .insert(edge_symbol, split);
+
+                    let leaf = self.new_node(position, OPEN_END, split, suffix);
+                    self.nodes[split].children.insert(symbol.clone(), leaf);
+
+                    self.nodes[next].start += self.active_length;
+                    self.nodes[next].parent = split;
+                    let next_symbol = self.symbol(self.nodes[next].start);
+                    self.nodes[split].children.insert(next_symbol, next);
+
+                    if let Some(internal) = last_internal.replace(split) {
+                        self.nodes[internal].link = split;
+                    }
+                }
+            }
+
+            self.remainder -= 1;
+
+            if self.active_node == ROOT && self.active_length > 0 {
+                self.active_length -= 1;
+                self.active_edge = position + 1 - self.remainder;
+            } else if self.active_node != ROOT {
+                self.active_node = self.nodes[self.active_node].link;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suffixes(tree: &SuffixTree<u8>) -> Vec<Vec<u8>> {
+        let mut suffixes = Vec::new();
+        let mut stack = vec![tree.root()];
+
+        // Reversed children so that leaves come out in order
+        while let Some(node) = stack.pop() {
+            if tree.is_leaf(node) {
+                suffixes.push(tree.path_label(node).to_vec());
+            }
+
+            let children: Vec<usize> = tree.children(node).collect();
+            stack.extend(children.into_iter().rev());
+        }
+
+        suffixes
+    }
+
+    #[test]
+    fn suffix_tree_unit_1() {
+        let string = b"mississippi";
+        let tree = SuffixTree::build(string);
+
+        let mut expected: Vec<Vec<u8>> = (0..=string.len()).map(|i| string[i..].to_vec()).collect();
+        expected.sort();
+
+        assert_eq!(suffixes(&tree), expected);
+        assert_eq!(tree.leaves().count(), string.len() + 1);
+
+        for leaf in tree.leaves() {
+            let (document, start) = tree.suffix(leaf).unwrap();
+            assert_eq!(tree.path_label(leaf), &tree.document(document)[start..]);
+        }
+
+        // Internal nodes branch, so their paths are right maximal repeats
+        let mut repeats: Vec<&[u8]> = tree
+            .internal_nodes()
+            .map(|node| tree.path_label(node))
+            .collect();
+        repeats.sort();
+
+        assert_eq!(repeats, [&b"i"[..], b"issi", b"p", b"s", b"si", b"ssi"]);
+        assert!(tree
+            .internal_nodes()
+            .all(|node| tree.children(node).count() >= 2));
+    }
+
+    #[test]
+    fn suffix_tree_queries_unit_1() {
+        let mut random = 1u32;
+        let string: Vec<u8> = (0..500)
+            .map(|_| {
+                random ^= random << 13;
+                random ^= random >> 17;
+                random ^= random << 5;
+                b'a' + (random % 3) as u8
+            })
+            .collect();
+        let tree = SuffixTree::build(&string);
+
+        for length in 0..6 {
+            for start in 0..string.len() - length {
+                let pattern = &string[start..start + length];
+                let mut expected: Vec<(usize, usize)> = (0..=string.len() - length)
+                    .filter(|&i| string[i..].starts_with(pattern))
+                    .map(|i| (0, i))
+                    .collect();
+                let mut found = tree.occurrences(pattern);
+                expected.sort();
+                found.sort();
+
+                assert_eq!(found, expected);
+            }
+        }
+
+        assert!(!tree.contains(b"abcabc"));
+        assert!(tree.string_depth(tree.root()) == 0);
+    }
+
+    #[test]
+    fn generalized_suffix_tree_unit_1() {
+        let mut tree = SuffixTree::new();
+        tree.add_document(b"xabxac".as_slice());
+        tree.add_document(b"abcabxabcd".as_slice());
+        tree.add_document(b"bxabc".as_slice());
+
+        // "bxa" and "xab" are both common to all three
+        assert_eq!(tree.longest_common_substring(3).unwrap().len(), 3);
+        assert!(tree.contains(tree.longest_common_substring(3).unwrap()));
+        assert_eq!(tree.longest_common_substring(2), Some(&b"bxabc"[..]));
+        assert_eq!(tree.longest_common_substring(1), Some(&b"abcabxabcd"[..]));
+        assert_eq!(tree.longest_common_substring(4), None);
+
+        // Nothing spans two documents
+        assert!(!tree.contains(b"acab"));
+        assert_eq!(tree.occurrences(b"xab").len(), 3);
+        assert_eq!(tree.leaves().count(), 7 + 11 + 6);
+
+        let chars: Vec<char> = "ёжик".chars().collect();
+        let tree = SuffixTree::build(&chars);
+
+        assert!(tree.contains(&['ж', 'и']));
+        assert_eq!(
+            tree.label(tree.children(tree.root()).last().unwrap()),
+            ['ё', 'ж', 'и', 'к']
+        );
+    }
+}
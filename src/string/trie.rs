@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-static ROOT: usize = 0;
+pub(crate) static ROOT: usize = 0;
 
 #[derive(Debug, Default, Clone)]
 struct State {
@@ -34,21 +34,37 @@ impl Trie {
 
     /// O(n) time
     pub fn add_string(&mut self, string: &str) {
+        self.insert(string);
+    }
+
+    /// Adds `string` and returns the node it ends at.
+    pub(crate) fn insert(&mut self, string: &str) -> usize {
         let mut vertex = ROOT;
 
         for c in string.chars() {
-            match self.nodes[vertex].to.get(&c) {
-                None => {
-                    let new_node = self.new_node();
-                    self.nodes[vertex].to.insert(c, new_node);
-                }
-                Some(_) => (),
+            if !self.nodes[vertex].to.contains_key(&c) {
+                let new_node = self.new_node();
+                self.nodes[vertex].to.insert(c, new_node);
             }
 
             vertex = self.nodes[vertex].to[&c];
         }
 
         self.nodes[vertex].is_terminal = true;
+
+        vertex
+    }
+
+    pub(crate) fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub(crate) fn next(&self, vertex: usize, c: char) -> Option<usize> {
+        self.nodes[vertex].to.get(&c).copied()
+    }
+
+    pub(crate) fn children(&self, vertex: usize) -> impl Iterator<Item = (char, usize)> + '_ {
+        self.nodes[vertex].to.iter().map(|(&c, &child)| (c, child))
     }
 
     /// O(n) time
@@ -80,9 +96,9 @@ mod tests {
             trie.add_string(string);
         }
 
-        assert_eq!(trie.contains("the"), true);
-        assert_eq!(trie.contains("these"), false);
-        assert_eq!(trie.contains("their"), true);
-        assert_eq!(trie.contains("thaw"), false);
+        assert!(trie.contains("the"));
+        assert!(!trie.contains("these"));
+        assert!(trie.contains("their"));
+        assert!(!trie.contains("thaw"));
     }
 }
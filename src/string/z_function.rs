@@ -1,7 +1,13 @@
 use std::cmp::min;
 
-/// O(n) time
-pub fn z_function(string: &str) -> Vec<usize> {
+/// `result[i]` is the length of the longest common prefix
+/// of `string` and `string[i..]`, 0 for `i = 0`.\
+/// O(n) time\
+/// O(n) space
+pub fn z_function<T>(string: &[T]) -> Vec<usize>
+where
+    T: Eq,
+{
     let strlen = string.len();
     let mut z_array = vec![0; strlen];
 
@@ -13,9 +19,7 @@ pub fn z_function(string: &str) -> Vec<usize> {
             z_array[i] = min(right - i + 1, z_array[i - left]);
         }
 
-        while i + z_array[i] < strlen
-            && string.chars().nth(z_array[i]) == string.chars().nth(i + z_array[i])
-        {
+        while i + z_array[i] < strlen && string[z_array[i]] == string[i + z_array[i]] {
             z_array[i] += 1;
         }
 
@@ -28,6 +32,16 @@ pub fn z_function(string: &str) -> Vec<usize> {
     z_array
 }
 
+/// `z_function` over the chars of `string`:
+/// one value per char, lengths counted in chars.\
+/// O(n) time\
+/// O(n) space
+pub fn z_function_str(string: &str) -> Vec<usize> {
+    let chars: Vec<char> = string.chars().collect();
+
+    z_function(&chars)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -35,18 +49,26 @@ mod tests {
     #[test]
     fn z_function_unit_1() {
         let string = "aaaaa";
-        assert_eq!(z_function(string), [0, 4, 3, 2, 1]);
+        assert_eq!(z_function_str(string), [0, 4, 3, 2, 1]);
     }
 
     #[test]
     fn z_function_unit_2() {
         let string = "aaabaab";
-        assert_eq!(z_function(string), [0, 2, 1, 0, 2, 1, 0]);
+        assert_eq!(z_function_str(string), [0, 2, 1, 0, 2, 1, 0]);
     }
 
     #[test]
     fn z_function_unit_3() {
         let string = "abacaba";
-        assert_eq!(z_function(string), [0, 0, 1, 0, 3, 0, 1]);
+        assert_eq!(z_function_str(string), [0, 0, 1, 0, 3, 0, 1]);
+    }
+
+    #[test]
+    fn z_function_multibyte_1() {
+        // 'é' and 'è' share their first byte
+        let string = "éaèé";
+        assert_eq!(z_function_str(string), [0, 0, 0, 1]);
+        assert_eq!(z_function(string.as_bytes()), [0, 0, 0, 1, 0, 2, 0]);
     }
 }